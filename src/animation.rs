@@ -0,0 +1,120 @@
+// ═══════════════════════════════════════════════════════════════════════════
+// 🎞️ LES ANIMATIONS : des planches de vignettes qui défilent
+// ═══════════════════════════════════════════════════════════════════════════
+//
+// Une image d'un pack peut être une planche : plusieurs vignettes de même
+// taille, rangées en colonnes et en lignes. Le manifeste du pack (voir
+// sprites.rs) donne la taille des vignettes et des animations nommées : les
+// vignettes à montrer, combien de temps chacune, et si elle recommence.
+//
+// Un objet qui a une `Animation` joue l'animation de ce nom dans la planche
+// de son rôle (son `Skin`) ; si son image n'est pas une planche, ou si elle
+// n'a pas cette animation, il reste sur sa première vignette :
+//
+//   - les aliens battent des ailes ("flap") ;
+//   - le chef abîmé passe à "damaged" ;
+//   - l'explosion se déroule une fois ("burst") ;
+//   - le vaisseau du joueur a la flamme de son réacteur ("thrust").
+//
+// En pause, tout reste figé sur la vignette en cours.
+
+use bevy::prelude::*;
+
+use crate::sprites::{Skin, Sprites};
+use crate::{Enemy, EnemyType};
+
+pub const FLAP: &str = "flap"; // Les aliens battent des ailes.
+pub const DAMAGED: &str = "damaged"; // Le chef qui a perdu son armure.
+pub const BURST: &str = "burst"; // L'explosion.
+pub const THRUST: &str = "thrust"; // La flamme du réacteur.
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnimationClip { pub frames: Vec<usize>, pub durations: Vec<f32>, pub looping: bool } // Les vignettes (dans la planche) et le temps de chacune, en secondes.
+
+impl AnimationClip {
+    pub fn uniform(frames: Vec<usize>, seconds: f32, looping: bool) -> Self { // Toutes les vignettes restent le même temps.
+        let durations = vec![seconds; frames.len()];
+        Self { frames, durations, looping }
+    }
+}
+
+#[derive(Component, Clone, Debug)]
+pub struct Animation { clip: &'static str, frame: usize, elapsed: f32 } // L'animation que joue un objet, et où il en est.
+
+impl Animation {
+    pub fn play(clip: &'static str) -> Self { Self { clip, frame: 0, elapsed: 0.0 } }
+
+    pub fn switch(&mut self, clip: &'static str) { // On change d'animation (sans recommencer si c'est déjà elle).
+        if self.clip != clip { *self = Self::play(clip); }
+    }
+
+    fn advance(&mut self, clip: &AnimationClip, seconds: f32) -> usize { // On avance le chrono ; la vignette à montrer.
+        self.frame = self.frame.min(clip.frames.len() - 1); // La planche a pu changer de pack en route.
+        self.elapsed += seconds;
+        while self.elapsed >= clip.durations[self.frame] {
+            if self.frame + 1 == clip.frames.len() && !clip.looping { self.elapsed = 0.0; break; } // Une animation finie reste sur sa dernière vignette.
+            self.elapsed -= clip.durations[self.frame];
+            self.frame = (self.frame + 1) % clip.frames.len();
+        }
+        clip.frames[self.frame]
+    }
+}
+
+impl Enemy {
+    fn clip(&self) -> &'static str { if self.kind == EnemyType::Boss && self.armor == 0 { DAMAGED } else { FLAP } } // Le chef sans armure montre ses dégâts.
+}
+
+pub fn enemy_clip_system(mut query: Query<(&Enemy, &mut Animation), Changed<Enemy>>) { // Un tir a changé l'état de l'alien.
+    for (enemy, mut animation) in query.iter_mut() { animation.switch(enemy.clip()); }
+}
+
+pub fn animation_system(time: Res<Time>, sprites: Res<Sprites>, mut query: Query<(&Skin, &mut Animation, &mut Sprite)>) { // Les vignettes défilent.
+    for (skin, mut animation, mut sprite) in query.iter_mut() {
+        let Some(clip) = sprites.clip(skin.0, animation.clip) else { continue };
+        let index = animation.advance(clip, time.delta_secs());
+        if let Some(atlas) = sprite.texture_atlas.as_mut() { atlas.index = index; }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_looping_clip_cycles_through_its_frames() {
+        let clip = AnimationClip::uniform(vec![4, 5], 0.25, true);
+        let mut animation = Animation::play(FLAP);
+        assert_eq!(animation.advance(&clip, 0.1), 4);
+        assert_eq!(animation.advance(&clip, 0.2), 5);
+        assert_eq!(animation.advance(&clip, 0.25), 4);
+    }
+
+    #[test]
+    fn a_one_shot_clip_stops_on_its_last_frame() {
+        let clip = AnimationClip { frames: vec![0, 1, 2], durations: vec![0.1, 0.3, 0.1], looping: false };
+        let mut animation = Animation::play(BURST);
+        assert_eq!(animation.advance(&clip, 0.15), 1);
+        assert_eq!(animation.advance(&clip, 0.3), 2, "la deuxième vignette dure plus longtemps");
+        assert_eq!(animation.advance(&clip, 5.0), 2);
+    }
+
+    #[test]
+    fn the_boss_shows_its_damage_once_its_armor_is_gone() {
+        let mut boss = Enemy::new(EnemyType::Boss);
+        assert_eq!(boss.clip(), FLAP);
+        boss.take_hit();
+        assert_eq!(boss.clip(), DAMAGED);
+        assert_eq!(Enemy::new(EnemyType::Soldier).clip(), FLAP);
+    }
+
+    #[test]
+    fn switching_to_the_same_clip_keeps_going() {
+        let clip = AnimationClip::uniform(vec![0, 1], 0.25, true);
+        let mut animation = Animation::play(FLAP);
+        animation.advance(&clip, 0.3);
+        animation.switch(FLAP);
+        assert_eq!(animation.frame, 1);
+        animation.switch(DAMAGED);
+        assert_eq!(animation.frame, 0);
+    }
+}
@@ -0,0 +1,278 @@
+// ═══════════════════════════════════════════════════════════════════════════
+// 🖌️ LES EXPORTS ASEPRITE : des planches toutes prêtes, sans table à la main
+// ═══════════════════════════════════════════════════════════════════════════
+//
+// Aseprite exporte une planche en deux fichiers : l'image (PNG) et un JSON
+// qui dit où est chaque vignette, combien de temps elle reste, et les tags
+// (les animations) et les slices (des zones nommées) dessinés dans le logiciel.
+// Dans un pack (voir sprites.rs), il suffit de donner le JSON à la place
+// de l'image :
+//
+//   Boss: "boss.json",
+//
+// et ce lecteur en fait la planche du jeu :
+//
+//   - chaque vignette garde sa place et sa durée (en millisecondes dans le
+//     JSON) ; l'export peut être en liste ("Array") ou en dictionnaire ("Hash") ;
+//   - chaque tag devient l'animation de ce nom ("flap", "damaged", "burst",
+//     "thrust"... voir animation.rs), dans son sens (forward, reverse,
+//     pingpong, pingpong_reverse). Un tag sans "repeat" recommence sans fin ;
+//     avec "repeat", il se joue ce nombre de fois et s'arrête sur sa dernière
+//     vignette (une explosion : repeat 1) ;
+//   - chaque slice devient une vignette de plus, à la fin de la planche, et
+//     une animation d'une seule image qui porte son nom.
+//
+// Il faut exporter sans "Rotate" (les vignettes tournées sont refusées) ; avec
+// "Trim", chaque vignette n'a plus la même taille et le dessin peut bouger.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, LoadContext};
+use bevy::prelude::*;
+use serde::de::{self, Deserializer, MapAccess, SeqAccess};
+use serde::Deserialize;
+
+use crate::animation::AnimationClip;
+use crate::levels::{self, LevelLoadError};
+use crate::sprites::{PackImage, Sheet};
+
+#[derive(Asset, TypePath, Debug)]
+pub struct AsepriteSheet { pub image: String, pub sheet: Sheet } // L'image de la planche (chemin complet) et ce qu'on en a lu.
+
+impl AsepriteSheet {
+    pub fn into_image(self) -> PackImage { PackImage { file: self.image, sheet: Some(self.sheet) } }
+}
+
+#[derive(Deserialize)]
+struct Export { frames: Frames, meta: Meta } // Le JSON tel qu'Aseprite l'écrit (le reste est ignoré).
+
+struct Frames(Vec<Frame>); // Les vignettes, dans l'ordre du fichier.
+
+#[derive(Deserialize)]
+struct Frame { frame: Bounds, #[serde(default)] rotated: bool, duration: u32 } // Sa place dans l'image et sa durée en millisecondes.
+
+#[derive(Deserialize, Clone, Copy)]
+struct Bounds { x: u32, y: u32, w: u32, h: u32 }
+
+#[derive(Deserialize)]
+struct Size { w: u32, h: u32 }
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Meta {
+    image: String, // Le PNG, relatif au JSON.
+    size: Size,
+    #[serde(default)]
+    frame_tags: Vec<Tag>,
+    #[serde(default)]
+    slices: Vec<Slice>,
+}
+
+#[derive(Deserialize)]
+struct Tag {
+    name: String,
+    from: usize, // Première et dernière vignette, comprises.
+    to: usize,
+    #[serde(default)]
+    direction: Direction,
+    #[serde(default)]
+    repeat: Option<String>, // Aseprite l'écrit en texte : "3".
+}
+
+#[derive(Deserialize, Default, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+enum Direction { #[default] Forward, Reverse, Pingpong, PingpongReverse }
+
+#[derive(Deserialize)]
+struct Slice { name: String, keys: Vec<SliceKey> }
+
+#[derive(Deserialize)]
+struct SliceKey { frame: usize, bounds: Bounds } // La zone, dans la vignette `frame`.
+
+impl<'de> Deserialize<'de> for Frames {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> { // En liste ou en dictionnaire, sans perdre l'ordre.
+        struct FramesVisitor;
+        impl<'de> de::Visitor<'de> for FramesVisitor {
+            type Value = Frames;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result { f.write_str("une liste ou un dictionnaire de vignettes") }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Frames, A::Error> {
+                let mut frames = Vec::new();
+                while let Some(frame) = seq.next_element()? { frames.push(frame); }
+                Ok(Frames(frames))
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Frames, A::Error> { // Les clés ("boss 0.aseprite"...) ne servent à rien.
+                let mut frames = Vec::new();
+                while let Some((_, frame)) = map.next_entry::<String, Frame>()? { frames.push(frame); }
+                Ok(Frames(frames))
+            }
+        }
+        deserializer.deserialize_any(FramesVisitor)
+    }
+}
+
+impl Bounds {
+    fn rect(self) -> URect { URect::new(self.x, self.y, self.x + self.w, self.y + self.h) }
+}
+
+impl Direction {
+    fn order(self, from: usize, to: usize) -> Vec<usize> { // Les vignettes d'un passage du tag.
+        let forward: Vec<usize> = (from..=to).collect();
+        let back = |frames: &[usize]| frames.iter().rev().skip(1).take(frames.len().saturating_sub(2)).copied().collect::<Vec<_>>(); // Le retour, sans répéter les bouts.
+        match self {
+            Direction::Forward => forward,
+            Direction::Reverse => forward.into_iter().rev().collect(),
+            Direction::Pingpong => { let back = back(&forward); [forward, back].concat() }
+            Direction::PingpongReverse => { let reverse: Vec<usize> = forward.into_iter().rev().collect(); let back = back(&reverse); [reverse, back].concat() }
+        }
+    }
+}
+
+pub(crate) fn parse(text: &str, path: &str, folder: &str) -> Result<AsepriteSheet, LevelLoadError> { // Le JSON lu et vérifié, transformé en planche.
+    let export: Export = serde_json::from_str(text).map_err(|err| LevelLoadError::Parse {
+        path: path.to_string(), line: err.line(), col: err.column(), message: err.to_string(),
+    })?;
+    let invalid = |key: &str, message: String| LevelLoadError::Invalid {
+        path: path.to_string(), line: text.find(key).map(|i| levels::line_of(text, i)).unwrap_or(1), message,
+    };
+    let Frames(frames) = export.frames;
+    let size = UVec2::new(export.meta.size.w, export.meta.size.h);
+    if frames.is_empty() { return Err(invalid("\"frames\"", "aucune vignette".into())); }
+    for (index, frame) in frames.iter().enumerate() {
+        if frame.rotated { return Err(invalid("\"rotated\": true", format!("la vignette {} est tournée : exporter sans \"Rotate\"", index))); }
+        if frame.duration == 0 { return Err(invalid("\"duration\": 0", format!("la vignette {} dure 0 ms", index))); }
+        let rect = frame.frame.rect();
+        if rect.is_empty() || rect.max.x > size.x || rect.max.y > size.y { return Err(invalid("\"frame\"", format!("la vignette {} sort de l'image ({} x {})", index, size.x, size.y))); }
+    }
+    let durations: Vec<f32> = frames.iter().map(|frame| frame.duration as f32 / 1000.0).collect();
+    let mut rects: Vec<URect> = frames.iter().map(|frame| frame.frame.rect()).collect();
+    let mut clips = HashMap::new();
+    for tag in &export.meta.frame_tags {
+        let tag_at = format!("\"name\": \"{}\"", tag.name);
+        if tag.from > tag.to || tag.to >= frames.len() { return Err(invalid(&tag_at, format!("le tag \"{}\" montre les vignettes {} à {} sur {}", tag.name, tag.from, tag.to, frames.len()))); }
+        let repeat = match tag.repeat.as_deref().map(str::parse::<usize>) {
+            None | Some(Ok(0)) => None, // Sans fin.
+            Some(Ok(count)) => Some(count),
+            Some(Err(_)) => return Err(invalid(&tag_at, format!("le tag \"{}\" : \"repeat\" n'est pas un nombre", tag.name))),
+        };
+        let order = tag.direction.order(tag.from, tag.to).repeat(repeat.unwrap_or(1));
+        let durations = order.iter().map(|&frame| durations[frame]).collect();
+        clips.insert(tag.name.clone(), AnimationClip { frames: order, durations, looping: repeat.is_none() });
+    }
+    for slice in &export.meta.slices {
+        let Some(key) = slice.keys.first() else { continue };
+        let slice_at = format!("\"name\": \"{}\"", slice.name);
+        let Some(frame) = rects.get(key.frame).copied() else { return Err(invalid(&slice_at, format!("la slice \"{}\" est sur la vignette {} sur {}", slice.name, key.frame, frames.len()))); };
+        let rect = key.bounds.rect();
+        if rect.is_empty() || rect.max.x > frame.width() || rect.max.y > frame.height() { // Dans sa vignette, donc aussi dans l'image.
+            return Err(invalid(&slice_at, format!("la slice \"{}\" sort de la vignette {} ({} x {})", slice.name, key.frame, frame.width(), frame.height())));
+        }
+        rects.push(URect::from_corners(frame.min + rect.min, frame.min + rect.max));
+        clips.entry(slice.name.clone()).or_insert_with(|| AnimationClip::uniform(vec![rects.len() - 1], 1.0, false)); // Un tag du même nom passe avant.
+    }
+    let image = if folder.is_empty() { export.meta.image } else { format!("{}/{}", folder, export.meta.image) };
+    Ok(AsepriteSheet { image, sheet: Sheet { size, frames: rects, clips } })
+}
+
+#[derive(Default, TypePath)]
+pub struct AsepriteLoader; // Le lecteur des fichiers `.json` d'Aseprite.
+
+impl AssetLoader for AsepriteLoader {
+    type Asset = AsepriteSheet;
+    type Settings = ();
+    type Error = LevelLoadError;
+
+    async fn load(&self, reader: &mut dyn Reader, _settings: &(), load_context: &mut LoadContext<'_>) -> Result<AsepriteSheet, LevelLoadError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let path = load_context.path().display().to_string();
+        let folder = load_context.path().parent().map(|p| p.to_string_lossy().replace('\\', "/")).unwrap_or_default();
+        parse(&String::from_utf8_lossy(&bytes), &path, &folder)
+    }
+
+    fn extensions(&self) -> &[&str] { &["json"] }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BOSS: &str = r##"{
+  "frames": {
+    "boss 0.aseprite": { "frame": { "x": 0, "y": 0, "w": 13, "h": 13 }, "rotated": false, "trimmed": false, "duration": 400 },
+    "boss 1.aseprite": { "frame": { "x": 13, "y": 0, "w": 13, "h": 13 }, "rotated": false, "trimmed": false, "duration": 200 },
+    "boss 2.aseprite": { "frame": { "x": 26, "y": 0, "w": 13, "h": 13 }, "rotated": false, "trimmed": false, "duration": 100 },
+    "boss 10.aseprite": { "frame": { "x": 39, "y": 0, "w": 13, "h": 13 }, "rotated": false, "trimmed": false, "duration": 100 }
+  },
+  "meta": {
+    "app": "https://www.aseprite.org/",
+    "image": "boss.png",
+    "size": { "w": 52, "h": 13 },
+    "frameTags": [
+      { "name": "flap", "from": 0, "to": 1, "direction": "forward", "color": "#000000ff" },
+      { "name": "damaged", "from": 1, "to": 3, "direction": "pingpong", "color": "#000000ff" },
+      { "name": "burst", "from": 2, "to": 3, "direction": "reverse", "repeat": "2", "color": "#000000ff" }
+    ],
+    "slices": [
+      { "name": "eyes", "color": "#0000ffff", "keys": [{ "frame": 1, "bounds": { "x": 3, "y": 4, "w": 7, "h": 2 } }] }
+    ]
+  }
+}"##;
+
+    fn boss() -> AsepriteSheet { parse(BOSS, "sprites/classic/boss.json", "sprites/classic").unwrap_or_else(|err| panic!("{}", err)) }
+
+    #[test]
+    fn frames_keep_their_place_and_order() { // "boss 10" reste la dernière, même si son nom se range avant "boss 2".
+        let boss = boss();
+        assert_eq!(boss.image, "sprites/classic/boss.png");
+        assert_eq!(boss.sheet.size, UVec2::new(52, 13));
+        assert_eq!(boss.sheet.frames[3], URect::new(39, 0, 52, 13));
+    }
+
+    #[test]
+    fn tags_become_clips_with_their_own_timing() {
+        let clips = boss().sheet.clips;
+        assert_eq!(clips["flap"], AnimationClip { frames: vec![0, 1], durations: vec![0.4, 0.2], looping: true });
+        assert_eq!(clips["damaged"].frames, vec![1, 2, 3, 2], "aller-retour sans répéter les bouts");
+        assert_eq!(clips["burst"], AnimationClip { frames: vec![3, 2, 3, 2], durations: vec![0.1; 4], looping: false });
+    }
+
+    #[test]
+    fn a_slice_is_one_more_frame() {
+        let sheet = boss().sheet;
+        assert_eq!(sheet.frames[4], URect::new(16, 4, 23, 6), "dans la vignette 1, qui commence à x = 13");
+        assert_eq!(sheet.clips["eyes"].frames, vec![4]);
+    }
+
+    #[test]
+    fn an_array_export_reads_the_same() {
+        let array = r#"{ "frames": [
+            { "filename": "a", "frame": { "x": 0, "y": 0, "w": 8, "h": 8 }, "duration": 50 },
+            { "filename": "b", "frame": { "x": 8, "y": 0, "w": 8, "h": 8 }, "duration": 50 }
+          ], "meta": { "image": "ship.png", "size": { "w": 16, "h": 8 }, "frameTags": [{ "name": "thrust", "from": 0, "to": 1 }] } }"#;
+        let sheet = parse(array, "ship.json", "").expect("un export en liste").sheet;
+        assert_eq!(sheet.frames.len(), 2);
+        assert_eq!(sheet.clips["thrust"], AnimationClip::uniform(vec![0, 1], 0.05, true));
+    }
+
+    #[test]
+    fn broken_exports_say_where() {
+        let outside = BOSS.replace("\"to\": 3, \"direction\": \"reverse\"", "\"to\": 7, \"direction\": \"reverse\"");
+        match parse(&outside, "boss.json", "") {
+            Err(LevelLoadError::Invalid { line, message, .. }) => { assert_eq!(line, 15); assert!(message.contains("burst"), "{}", message); }
+            other => panic!("le tag devait être refusé : {:?}", other.map(|_| ())),
+        }
+        let wide_slice = BOSS.replace("\"w\": 7, \"h\": 2", "\"w\": 12, \"h\": 2"); // x = 3 + 12 dépasse la vignette de 13 pixels.
+        match parse(&wide_slice, "boss.json", "") {
+            Err(LevelLoadError::Invalid { line, message, .. }) => { assert_eq!(line, 18); assert!(message.contains("eyes"), "{}", message); }
+            other => panic!("la slice devait être refusée : {:?}", other.map(|_| ())),
+        }
+        let rotated = BOSS.replacen("\"rotated\": false", "\"rotated\": true", 1);
+        assert!(matches!(parse(&rotated, "boss.json", ""), Err(LevelLoadError::Invalid { line: 3, .. })));
+        assert!(matches!(parse("{ \"frames\": ", "boss.json", ""), Err(LevelLoadError::Parse { line: 1, .. })));
+    }
+}
@@ -0,0 +1,117 @@
+// ═══════════════════════════════════════════════════════════════════════════
+// 🎯 LE STAGE BONUS ("CHALLENGING STAGE") : 40 aliens qui ne tirent jamais
+// ═══════════════════════════════════════════════════════════════════════════
+//
+// Tous les quelques stages, Galaga offre une pause : 5 groupes de 8 aliens
+// traversent l'écran en suivant des figures acrobatiques. Ils ne tirent pas
+// et ne foncent pas sur toi : il faut juste en abattre le plus possible. À la
+// fin, on affiche "NUMBER OF HITS" et un bonus, ou "PERFECT !" si tu les as
+// tous eus.
+
+use bevy::prelude::*;
+
+use crate::animation::{self, Animation};
+use crate::collider::Collider;
+use crate::paths::{PathFollower, Spline};
+use crate::playfield::Playfield;
+use crate::sprites::{Skin, SpriteId, Sprites};
+use crate::tuning::Tuning;
+use crate::{Enemy, EnemyType};
+
+pub const FLYER_COUNT: usize = 40; // Le nombre total d'aliens du stage bonus.
+const GROUP_SIZE: usize = 8; // Les aliens arrivent par groupes de 8.
+pub const INTRO_DELAY: f32 = 2.0; // Le temps pendant lequel on affiche "CHALLENGING STAGE" avant le premier groupe.
+const GROUP_DELAY: f32 = 1.6; // La pause entre deux groupes.
+const FLYER_DELAY: f32 = 0.18; // L'écart entre deux aliens du même groupe.
+const FLYER_SPEED_FACTOR: f32 = 2.2; // Les acrobates vont 2,2 fois plus vite que la vitesse de base.
+const HIT_BONUS: u32 = 100; // Chaque alien touché rapporte 100 points de bonus.
+const PERFECT_BONUS: u32 = 10_000; // Le bonus si on les a tous eus.
+
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub enum StageKind { #[default] Normal, Challenging } // Un stage normal ou un stage bonus.
+
+#[derive(Component)] pub struct ChallengeFlyer; // Un alien acrobate qui ne tire jamais et ne fonce pas sur le joueur.
+
+// Les figures sont dessinées en fractions de la demi-largeur et de la demi-hauteur de l'écran.
+const SWOOP_DOWN: [Vec2; 8] = [ // Il tombe du coin haut, fait une boucle et sort à droite.
+    Vec2::new(-0.6, 1.1), Vec2::new(-0.4, 0.4), Vec2::new(0.0, -0.2), Vec2::new(0.4, 0.1),
+    Vec2::new(0.3, 0.5), Vec2::new(-0.1, 0.3), Vec2::new(0.2, -0.4), Vec2::new(1.2, -0.6),
+];
+const SIDE_LOOP: [Vec2; 9] = [ // Il arrive par le côté, fait un looping au milieu et ressort en montant.
+    Vec2::new(-1.1, 0.2), Vec2::new(-0.4, 0.25), Vec2::new(0.1, 0.1), Vec2::new(0.25, -0.15), Vec2::new(0.05, -0.35),
+    Vec2::new(-0.15, -0.15), Vec2::new(0.0, 0.1), Vec2::new(0.5, 0.3), Vec2::new(1.1, 0.5),
+];
+const FIGURE_EIGHT: [Vec2; 11] = [ // Il plonge du milieu, dessine un huit et repart par le haut.
+    Vec2::new(0.0, 1.1), Vec2::new(0.0, 0.3), Vec2::new(-0.3, -0.2), Vec2::new(-0.6, 0.0), Vec2::new(-0.4, 0.4), Vec2::new(0.0, 0.2),
+    Vec2::new(0.4, 0.4), Vec2::new(0.6, 0.0), Vec2::new(0.3, -0.2), Vec2::new(0.0, 0.3), Vec2::new(0.0, 1.15),
+];
+
+pub fn is_challenging(stage: u32) -> bool { stage >= 3 && (stage - 3).is_multiple_of(4) } // Comme l'arcade : stages 3, 7, 11, 15...
+
+pub fn bonus(hits: usize) -> u32 { if hits >= FLYER_COUNT { PERFECT_BONUS } else { hits as u32 * HIT_BONUS } }
+
+pub fn results_message(hits: usize) -> String { // Le tableau de fin du stage bonus.
+    let perfect = if hits >= FLYER_COUNT { "PERFECT !\n" } else { "" };
+    format!("{}NUMBER OF HITS  {}\nBONUS  {}", perfect, hits, bonus(hits))
+}
+
+pub fn next_spawn_delay(spawned: usize) -> f32 { // Le temps avant le prochain alien : une pause entre les groupes.
+    if spawned.is_multiple_of(GROUP_SIZE) { GROUP_DELAY } else { FLYER_DELAY }
+}
+
+pub fn spawn_flyer(commands: &mut Commands, sprites: &Sprites, tuning: &Tuning, index: usize, playfield: &Playfield) { // Un acrobate de plus.
+    let group = index / GROUP_SIZE;
+    let (points, mirror): (&[Vec2], bool) = match group { // Chaque groupe a sa figure ; les groupes pairs et impairs sont en miroir.
+        0 => (&SWOOP_DOWN, false),
+        1 => (&SWOOP_DOWN, true),
+        2 => (&SIDE_LOOP, false),
+        3 => (&SIDE_LOOP, true),
+        _ => (&FIGURE_EIGHT, false),
+    };
+    let half = playfield.size / 2.0;
+    let points: Vec<Vec2> = points.iter().map(|p| Vec2::new(if mirror { -p.x } else { p.x }, p.y) * half).collect();
+    let start = points[0];
+    let is_boss = group == 4 && index % 2 == 1; // Le dernier groupe mélange des chefs avec les soldats.
+    let enemy = Enemy::new(if is_boss { EnemyType::Boss } else { EnemyType::Soldier });
+    let sprite_id = match group { 0 | 1 => SpriteId::SoldierTop, 2 | 3 => SpriteId::SoldierRight, _ if is_boss => SpriteId::Boss, _ => SpriteId::SoldierLeft };
+    commands.spawn((
+        ChallengeFlyer,
+        Collider::enemy(tuning, enemy.kind),
+        Skin(sprite_id),
+        Animation::play(animation::FLAP),
+        Sprite { custom_size: Some(tuning.enemy_size_of(enemy.kind)), color: enemy.tint(), ..sprites.sprite(sprite_id) },
+        enemy,
+        PathFollower::new(Spline::CatmullRom(points), tuning.enemy_speed * FLYER_SPEED_FACTOR),
+        Transform::from_translation(start.extend(0.0)),
+    ));
+}
+
+pub fn challenge_flyer_system(mut commands: Commands, query: Query<(Entity, &PathFollower), With<ChallengeFlyer>>) { // Les acrobates qui ont fini leur figure s'en vont.
+    for (entity, follower) in query.iter() {
+        if follower.finished() { commands.entity(entity).despawn(); }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_fourth_stage_from_the_third_is_a_bonus() {
+        assert_eq!((1..=12).filter(|&stage| is_challenging(stage)).collect::<Vec<_>>(), [3, 7, 11]);
+        assert!(!is_challenging(0));
+    }
+
+    #[test]
+    fn each_hit_pays_until_the_perfect_bonus() {
+        assert_eq!(bonus(0), 0);
+        assert_eq!(bonus(39), 3_900);
+        assert_eq!(bonus(FLYER_COUNT), PERFECT_BONUS);
+        assert_eq!(results_message(FLYER_COUNT), "PERFECT !\nNUMBER OF HITS  40\nBONUS  10000");
+    }
+
+    #[test]
+    fn groups_of_eight_wait_for_each_other() {
+        assert_eq!((1..=9).map(next_spawn_delay).collect::<Vec<_>>(), [FLYER_DELAY, FLYER_DELAY, FLYER_DELAY, FLYER_DELAY, FLYER_DELAY, FLYER_DELAY, FLYER_DELAY, GROUP_DELAY, FLYER_DELAY]);
+    }
+}
@@ -0,0 +1,73 @@
+// ═══════════════════════════════════════════════════════════════════════════
+// ⌨️ LA LIGNE DE COMMANDE : les options qu'on donne au lancement du jeu
+// ═══════════════════════════════════════════════════════════════════════════
+//
+// Par exemple : `cargo run -- --seed 1234` pour rejouer une partie précise,
+// ou `cargo run -- --replay partie.xgr` pour revoir une partie enregistrée,
+// ou `cargo run -- --headless --frames 36000` pour simuler sans fenêtre,
+// ou `cargo run --release -- --bench-collisions` pour mesurer la détection des chocs.
+
+use std::fmt;
+use std::path::PathBuf;
+
+#[derive(Debug, Default)]
+pub struct CliOptions { // Ce qu'on a demandé au lancement.
+    pub seed: Option<u64>, // La graine du hasard (sinon, une graine au hasard).
+    pub record: Option<PathBuf>, // Où enregistrer le replay de la partie.
+    pub replay: Option<PathBuf>, // Le replay à rejouer.
+    pub headless: bool, // Sans fenêtre : on simule et on affiche le bilan.
+    pub frames: Option<u64>, // Combien de pas simuler sans fenêtre (ou mesurer).
+    pub bench_collisions: bool, // On mesure la détection des chocs avec 2 000 balles et 200 aliens, puis on quitte.
+}
+
+#[derive(Debug)]
+pub enum CliError { // Une option mal écrite.
+    MissingValue(String),
+    BadValue { option: String, value: String },
+    Unknown(String),
+    Conflict(&'static str, &'static str), // Deux options qui ne vont pas ensemble.
+    Requires(&'static str, &'static str), // Une option qui n'a de sens qu'avec une autre.
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::MissingValue(option) => write!(f, "il manque une valeur après `{}`", option),
+            CliError::BadValue { option, value } => write!(f, "valeur invalide pour `{}` : `{}`", option, value),
+            CliError::Unknown(option) => write!(f, "option inconnue : `{}`", option),
+            CliError::Conflict(a, b) => write!(f, "`{}` et `{}` ne vont pas ensemble", a, b),
+            CliError::Requires(a, b) => write!(f, "`{}` demande aussi `{}`", a, b),
+        }
+    }
+}
+
+pub const USAGE: &str = "options : --seed <nombre> | --record <fichier> | --replay <fichier> | --headless [--frames <nombre>] | --bench-collisions [--frames <nombre>]";
+
+impl CliOptions {
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, CliError> { // On lit les options une par une.
+        let mut options = CliOptions::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--seed" => {
+                    let value = args.next().ok_or_else(|| CliError::MissingValue(arg.clone()))?;
+                    options.seed = Some(value.parse().map_err(|_| CliError::BadValue { option: arg.clone(), value })?);
+                }
+                "--record" => options.record = Some(args.next().ok_or_else(|| CliError::MissingValue(arg.clone()))?.into()),
+                "--replay" => options.replay = Some(args.next().ok_or_else(|| CliError::MissingValue(arg.clone()))?.into()),
+                "--headless" => options.headless = true,
+                "--bench-collisions" => options.bench_collisions = true,
+                "--frames" => {
+                    let value = args.next().ok_or_else(|| CliError::MissingValue(arg.clone()))?;
+                    options.frames = Some(value.parse().map_err(|_| CliError::BadValue { option: arg.clone(), value })?);
+                }
+                _ => return Err(CliError::Unknown(arg)),
+            }
+        }
+        if options.replay.is_some() && options.record.is_some() { return Err(CliError::Conflict("--record", "--replay")); }
+        if options.replay.is_some() && options.seed.is_some() { return Err(CliError::Conflict("--seed", "--replay")); } // La graine est dans le replay.
+        if options.frames.is_some() && !options.headless && !options.bench_collisions { return Err(CliError::Requires("--frames", "--headless")); }
+        if options.bench_collisions && (options.replay.is_some() || options.record.is_some()) { return Err(CliError::Conflict("--bench-collisions", "--record/--replay")); }
+        Ok(options)
+    }
+}
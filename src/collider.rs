@@ -0,0 +1,252 @@
+// ═══════════════════════════════════════════════════════════════════════════
+// 💥 LES ZONES DE CHOC : une forme et une couche pour chaque objet qui peut toucher
+// ═══════════════════════════════════════════════════════════════════════════
+//
+// Avant, chaque choc était une distance écrite à la main (25, 50, 15...) qui
+// ne suivait pas la taille des images : un alien de 25 px se faisait toucher
+// à 25 px de son centre. Maintenant, chaque objet porte un `Collider` :
+//
+//   - une forme, calculée avec les tailles du fichier de réglages : un
+//     rectangle pour le vaisseau, les balles et les prisonniers, un cercle
+//     pour les aliens et les bonus ;
+//   - une couche, qui dit qui peut toucher qui :
+//
+//       Player ─── Enemy, EnemyBullet, Pickup
+//       PlayerBullet ─── Enemy
+//
+// Une seule passe (`detect_contacts_system`) range chaque paire qui se touche
+// dans `Contacts`, une fois et une seule, juste après les déplacements. Les
+// règles du jeu (collision_system, le prisonnier du rayon...) lisent cette
+// liste au lieu de refaire leurs propres calculs de distance.
+//
+// Pour ne pas comparer chaque balle avec chaque alien (2 000 balles x 200
+// aliens = 400 000 tests par pas), la passe range d'abord tout le monde dans
+// une grille de cases de 64 px, refaite à chaque pas : on ne teste que les
+// objets des cases voisines. Le résultat est exactement celui de la passe
+// naïve (`find_contacts_naive`), dans le même ordre. Pour mesurer :
+//
+//   cargo run --release -- --bench-collisions
+
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+
+use crate::tractor::DualFighter;
+use crate::tuning::Tuning;
+use crate::{Enemy, EnemyType};
+
+const PICKUP_RADIUS: f32 = 7.5; // Les bonus sont des carrés de 15 px.
+const CELL_SIZE: f32 = 64.0; // Une case de la grille : un peu plus qu'un chef.
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Shape { Aabb { half: Vec2 }, Circle { radius: f32 } } // Un rectangle droit (par sa demi-taille) ou un cercle.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Layer { Player, PlayerBullet, Enemy, EnemyBullet, Pickup } // Les couches, dans l'ordre où les paires sont rangées.
+
+const INTERACTIONS: [(Layer, Layer); 4] = [ // Les seules couches qui se touchent.
+    (Layer::Player, Layer::Enemy),
+    (Layer::Player, Layer::EnemyBullet),
+    (Layer::Player, Layer::Pickup),
+    (Layer::PlayerBullet, Layer::Enemy),
+];
+
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub struct Collider { pub layer: Layer, pub shape: Shape, pub offset: Vec2 } // La zone de choc, décalée de `offset` par rapport au centre de l'objet.
+
+impl Collider {
+    fn aabb(layer: Layer, size: Vec2) -> Self { Self { layer, shape: Shape::Aabb { half: size / 2.0 }, offset: Vec2::ZERO } }
+
+    fn circle(layer: Layer, radius: f32) -> Self { Self { layer, shape: Shape::Circle { radius }, offset: Vec2::ZERO } }
+
+    pub fn player(tuning: &Tuning, dual: bool) -> Self { // Avec le double chasseur, la zone s'étend sur l'ailier, à droite.
+        let mut collider = Self::aabb(Layer::Player, tuning.player_size);
+        if dual {
+            collider = Self::aabb(Layer::Player, tuning.player_size + Vec2::new(tuning.dual_offset(), 0.0));
+            collider.offset.x = tuning.dual_offset() / 2.0;
+        }
+        collider
+    }
+
+    pub fn enemy(tuning: &Tuning, kind: EnemyType) -> Self { Self::circle(Layer::Enemy, tuning.enemy_size_of(kind).x / 2.0) }
+
+    pub fn captive(tuning: &Tuning) -> Self { Self::aabb(Layer::Enemy, tuning.player_size) } // Le prisonnier du chef : une cible comme un alien.
+
+    pub fn bullet(tuning: &Tuning, from_player: bool) -> Self {
+        Self::aabb(if from_player { Layer::PlayerBullet } else { Layer::EnemyBullet }, tuning.bullet_size)
+    }
+
+    pub fn pickup() -> Self { Self::circle(Layer::Pickup, PICKUP_RADIUS) }
+
+    fn cells(&self, pos: Vec2) -> (IVec2, IVec2) { // Les cases de la grille que la zone recouvre (coin bas-gauche, coin haut-droit).
+        let half = match self.shape { Shape::Aabb { half } => half, Shape::Circle { radius } => Vec2::splat(radius) };
+        let center = pos + self.offset;
+        (((center - half) / CELL_SIZE).floor().as_ivec2(), ((center + half) / CELL_SIZE).floor().as_ivec2())
+    }
+}
+
+pub fn overlaps(a: &Collider, a_pos: Vec2, b: &Collider, b_pos: Vec2) -> bool { // Les deux zones se chevauchent-elles ?
+    let (a_pos, b_pos) = (a_pos + a.offset, b_pos + b.offset);
+    match (a.shape, b.shape) {
+        (Shape::Aabb { half: ha }, Shape::Aabb { half: hb }) => {
+            let d = (a_pos - b_pos).abs();
+            d.x < ha.x + hb.x && d.y < ha.y + hb.y
+        }
+        (Shape::Circle { radius: ra }, Shape::Circle { radius: rb }) => a_pos.distance_squared(b_pos) < (ra + rb) * (ra + rb),
+        (Shape::Aabb { half }, Shape::Circle { radius }) => circle_touches_box(b_pos, radius, a_pos, half),
+        (Shape::Circle { radius }, Shape::Aabb { half }) => circle_touches_box(a_pos, radius, b_pos, half),
+    }
+}
+
+fn circle_touches_box(center: Vec2, radius: f32, box_center: Vec2, half: Vec2) -> bool { // Le point du rectangle le plus proche du cercle est-il dedans ?
+    let closest = center.clamp(box_center - half, box_center + half);
+    center.distance_squared(closest) < radius * radius
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Contact { pub a: Entity, pub a_layer: Layer, pub b: Entity, pub b_layer: Layer } // Deux objets qui se touchent, la plus petite couche en premier.
+
+#[derive(Resource, Default)]
+pub struct Contacts(pub Vec<Contact>); // Toutes les paires qui se touchent pendant ce pas.
+
+impl Contacts {
+    pub fn between(&self, a_layer: Layer, b_layer: Layer) -> impl Iterator<Item = (Entity, Entity)> + '_ { // Les paires d'une interaction donnée.
+        self.0.iter().filter(move |c| c.a_layer == a_layer && c.b_layer == b_layer).map(|c| (c.a, c.b))
+    }
+}
+
+#[derive(Default)]
+pub struct SpatialGrid { cells: HashMap<(Layer, IVec2), Vec<usize>>, candidates: Vec<usize> } // Les objets rangés par couche et par case (gardée d'un pas à l'autre pour ne pas tout réallouer).
+
+impl SpatialGrid {
+    fn rebuild(&mut self, colliders: &[(Entity, Vec2, Collider)]) { // On vide les cases et on range chaque objet qu'on peut toucher.
+        for cell in self.cells.values_mut() { cell.clear(); }
+        for (index, (_, pos, collider)) in colliders.iter().enumerate() {
+            if !INTERACTIONS.iter().any(|(_, b_layer)| *b_layer == collider.layer) { continue; } // Le vaisseau et ses balles ne sont jamais cherchés.
+            let (min, max) = collider.cells(*pos);
+            for x in min.x..=max.x {
+                for y in min.y..=max.y { self.cells.entry((collider.layer, IVec2::new(x, y))).or_default().push(index); }
+            }
+        }
+    }
+}
+
+pub fn find_contacts(colliders: &[(Entity, Vec2, Collider)], grid: &mut SpatialGrid) -> Vec<Contact> { // Chaque paire qui se touche, une seule fois, en ne testant que les cases voisines.
+    grid.rebuild(colliders);
+    let mut contacts = Vec::new();
+    for (a_layer, b_layer) in INTERACTIONS {
+        for (a, a_pos, a_col) in colliders.iter().filter(|(_, _, c)| c.layer == a_layer) {
+            let (min, max) = a_col.cells(*a_pos);
+            grid.candidates.clear();
+            for x in min.x..=max.x {
+                for y in min.y..=max.y {
+                    if let Some(cell) = grid.cells.get(&(b_layer, IVec2::new(x, y))) { grid.candidates.extend_from_slice(cell); }
+                }
+            }
+            grid.candidates.sort_unstable(); // Dans l'ordre de la passe naïve...
+            grid.candidates.dedup(); // ... et une seule fois, même à cheval sur plusieurs cases.
+            for &index in &grid.candidates {
+                let (b, b_pos, b_col) = &colliders[index];
+                if overlaps(a_col, *a_pos, b_col, *b_pos) { contacts.push(Contact { a: *a, a_layer, b: *b, b_layer }); }
+            }
+        }
+    }
+    contacts
+}
+
+pub fn find_contacts_naive(colliders: &[(Entity, Vec2, Collider)]) -> Vec<Contact> { // Tout le monde contre tout le monde : la référence pour les tests et la mesure.
+    let mut contacts = Vec::new();
+    for (a_layer, b_layer) in INTERACTIONS {
+        for (a, a_pos, a_col) in colliders.iter().filter(|(_, _, c)| c.layer == a_layer) {
+            for (b, b_pos, b_col) in colliders.iter().filter(|(_, _, c)| c.layer == b_layer) {
+                if overlaps(a_col, *a_pos, b_col, *b_pos) { contacts.push(Contact { a: *a, a_layer, b: *b, b_layer }); }
+            }
+        }
+    }
+    contacts
+}
+
+pub fn detect_contacts_system(mut contacts: ResMut<Contacts>, mut grid: Local<SpatialGrid>, query: Query<(Entity, &Transform, &Collider)>) { // La passe unique de détection.
+    let colliders: Vec<(Entity, Vec2, Collider)> = query.iter().map(|(e, t, c)| (e, t.translation.xy(), *c)).collect();
+    contacts.0 = find_contacts(&colliders, &mut grid);
+}
+
+#[allow(clippy::type_complexity)]
+pub fn refit_colliders_system( // Les tailles du fichier de réglages ont changé : les zones de choc suivent.
+    tuning: Res<Tuning>,
+    mut query: Query<(&mut Collider, Option<&Enemy>, Option<&crate::Bullet>, Has<DualFighter>)>,
+) {
+    for (mut collider, enemy, bullet, dual) in query.iter_mut() {
+        let fitted = match (collider.layer, enemy, bullet) {
+            (Layer::Player, ..) => Collider::player(&tuning, dual),
+            (Layer::Enemy, Some(enemy), _) => Collider::enemy(&tuning, enemy.kind),
+            (Layer::Enemy, None, _) => Collider::captive(&tuning),
+            (_, _, Some(bullet)) => Collider::bullet(&tuning, bullet.from_player),
+            _ => continue,
+        };
+        if *collider != fitted { *collider = fitted; }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(index: u32, x: f32, collider: Collider) -> (Entity, Vec2, Collider) { (Entity::from_raw_u32(index).unwrap(), Vec2::new(x, 0.0), collider) }
+
+    #[test]
+    fn circle_against_box_uses_the_closest_point() {
+        let enemy = Collider::enemy(&Tuning::default(), EnemyType::Soldier); // Rayon 12,5.
+        let bullet = Collider::bullet(&Tuning::default(), true); // 5 x 15.
+        assert!(overlaps(&enemy, Vec2::ZERO, &bullet, Vec2::new(14.0, 0.0)));
+        assert!(!overlaps(&enemy, Vec2::ZERO, &bullet, Vec2::new(16.0, 0.0)));
+        assert!(!overlaps(&enemy, Vec2::ZERO, &bullet, Vec2::new(12.0, 19.0)), "le coin du rectangle est trop loin");
+    }
+
+    #[test]
+    fn every_touching_pair_is_listed_once() {
+        let tuning = Tuning::default();
+        let colliders = [
+            at(1, 0.0, Collider::enemy(&tuning, EnemyType::Soldier)),
+            at(2, 0.0, Collider::bullet(&tuning, true)),
+            at(3, 4.0, Collider::bullet(&tuning, true)),
+            at(4, 0.0, Collider::bullet(&tuning, false)), // Une balle alien ne touche pas un alien.
+            at(5, 0.0, Collider::enemy(&tuning, EnemyType::Boss)),
+        ];
+        let pairs: Vec<(u32, u32)> = find_contacts(&colliders, &mut SpatialGrid::default()).iter().map(|c| (c.a.index(), c.b.index())).collect();
+        assert_eq!(pairs, [(2, 1), (2, 5), (3, 1), (3, 5)]);
+    }
+
+    #[test]
+    fn the_dual_fighter_is_hit_on_its_wingman() {
+        let tuning = Tuning::default();
+        let shot = Collider::bullet(&tuning, false);
+        let wingman = Vec2::new(tuning.dual_offset(), 0.0);
+        assert!(!overlaps(&Collider::player(&tuning, false), Vec2::ZERO, &shot, wingman));
+        assert!(overlaps(&Collider::player(&tuning, true), Vec2::ZERO, &shot, wingman));
+    }
+
+    #[test]
+    fn the_grid_finds_what_the_naive_pass_finds() { // Même paires, même ordre, y compris à cheval sur plusieurs cases.
+        use rand::{Rng, SeedableRng};
+        let tuning = Tuning::default();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        let mut grid = SpatialGrid::default();
+        for round in 0..5 { // La grille sert plusieurs fois, comme d'un pas à l'autre.
+            let colliders: Vec<(Entity, Vec2, Collider)> = (1..400).map(|i| {
+                let collider = match i % 5 {
+                    0 => Collider::player(&tuning, i % 10 == 0),
+                    1 => Collider::bullet(&tuning, true),
+                    2 => Collider::enemy(&tuning, if i % 3 == 0 { EnemyType::Boss } else { EnemyType::Soldier }),
+                    3 => Collider::bullet(&tuning, false),
+                    _ => Collider::pickup(),
+                };
+                let pos = Vec2::new(rng.gen_range(-200.0..200.0), rng.gen_range(-150.0..150.0)) * (round + 1) as f32 / 3.0;
+                (Entity::from_raw_u32(i).unwrap(), pos, collider)
+            }).collect();
+            let naive = find_contacts_naive(&colliders);
+            assert!(!naive.is_empty());
+            assert_eq!(find_contacts(&colliders, &mut grid), naive);
+        }
+    }
+}
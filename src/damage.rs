@@ -0,0 +1,75 @@
+// ═══════════════════════════════════════════════════════════════════════════
+// 🛡️ LES DÉGÂTS : certains aliens encaissent plusieurs tirs
+// ═══════════════════════════════════════════════════════════════════════════
+//
+// Chaque alien a une armure (les tirs qu'il encaisse sans mourir) et des
+// points de vie. Le chef Galaga est vert avec son armure, il devient bleu au
+// premier tir, et il meurt au deuxième. Chaque tir le fait clignoter, et les
+// points gagnés dépendent de son état et du fait qu'il plongeait ou non.
+
+use bevy::prelude::*;
+
+use crate::{Enemy, EnemyType};
+
+const FLASH_DURATION: f32 = 0.12; // Le temps du flash blanc quand un alien est touché.
+const FLASH_SCALE: f32 = 1.25; // L'alien gonfle un peu pendant le flash.
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HitOutcome { ArmorCracked, Destroyed } // Ce qu'un tir a fait à l'alien.
+
+#[derive(Component)]
+pub struct HitFlash { timer: Timer } // L'alien clignote parce qu'il vient d'être touché.
+
+impl EnemyType {
+    pub fn max_armor(&self) -> i32 { match self { EnemyType::Soldier => 0, EnemyType::Boss => 1 } } // Combien de tirs il encaisse sans mourir.
+    pub fn max_health(&self) -> i32 { match self { EnemyType::Soldier | EnemyType::Boss => 1 } } // Combien de tirs il faut ensuite pour l'abattre.
+    fn base_score(&self) -> u32 { match self { EnemyType::Soldier => 10, EnemyType::Boss => 100 } }
+}
+
+impl Enemy {
+    pub fn new(kind: EnemyType) -> Self { Self { armor: kind.max_armor(), health: kind.max_health(), kind } }
+
+    pub fn take_hit(&mut self) -> HitOutcome { // Un tir enlève d'abord l'armure, puis la vie.
+        if self.armor > 0 { self.armor -= 1; return HitOutcome::ArmorCracked; }
+        self.health -= 1;
+        if self.health <= 0 { HitOutcome::Destroyed } else { HitOutcome::ArmorCracked }
+    }
+
+    pub fn tint(&self) -> Color { // La couleur de l'alien selon son état.
+        match self.kind {
+            EnemyType::Boss if self.armor > 0 => Color::srgb(0.4, 1.0, 0.4), // Chef intact : vert.
+            EnemyType::Boss => Color::srgb(0.45, 0.6, 1.0), // Chef abîmé : bleu.
+            EnemyType::Soldier => Color::WHITE,
+        }
+    }
+}
+
+pub fn score_for_hit(kind: &EnemyType, outcome: HitOutcome, diving: bool) -> u32 { // Combien de points rapporte ce tir ?
+    let base = match outcome {
+        HitOutcome::ArmorCracked => kind.base_score() / 10, // Abîmer un alien rapporte un petit bonus.
+        HitOutcome::Destroyed => kind.base_score(),
+    };
+    if diving { base * 2 } else { base } // Un alien en piqué rapporte le double.
+}
+
+pub fn flash(commands: &mut Commands, entity: Entity) { // On déclenche le clignotement d'un alien touché (s'il n'a pas été abattu entre-temps).
+    if let Ok(mut cmd) = commands.get_entity(entity) { cmd.try_insert(HitFlash { timer: Timer::from_seconds(FLASH_DURATION, TimerMode::Once) }); }
+}
+
+pub fn hit_flash_system( // Le flash blanc, puis la nouvelle couleur de l'alien.
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &Enemy, &mut HitFlash, &mut Sprite, &mut Transform)>,
+) {
+    for (entity, enemy, mut flash, mut sprite, mut trans) in query.iter_mut() {
+        flash.timer.tick(time.delta());
+        if flash.timer.is_finished() {
+            sprite.color = enemy.tint();
+            trans.scale = Vec3::ONE;
+            commands.entity(entity).remove::<HitFlash>();
+        } else {
+            sprite.color = Color::srgb(1.0, 1.0, 1.0).mix(&enemy.tint(), flash.timer.fraction()); // Tout blanc, puis on revient vers la couleur.
+            trans.scale = Vec3::splat(FLASH_SCALE - (FLASH_SCALE - 1.0) * flash.timer.fraction());
+        }
+    }
+}
@@ -0,0 +1,190 @@
+// ═══════════════════════════════════════════════════════════════════════════
+// 💥 LES PLONGEONS : les aliens quittent la grille pour foncer sur le joueur
+// ═══════════════════════════════════════════════════════════════════════════
+//
+// De temps en temps, un alien rangé dans la formation part en piqué le long
+// d'une trajectoire dessinée à l'avance (une boucle, un grand virage ou un
+// mitraillage en rase-mottes). S'il survit, il revient à sa place, ou bien il
+// sort par le bas de l'écran et réapparaît en haut. Plus le niveau est élevé,
+// plus les plongeons sont fréquents, rapides et nombreux.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::formation::{Flight, Formation, FormationSlot};
+use crate::lives::Lives;
+use crate::paths::{PathFollower, Spline};
+use crate::playfield::Playfield;
+use crate::tractor::{self, BeingCaptured, CapturedFighter, DualFighter, TractorDive};
+use crate::rng::GameRng;
+use crate::tuning::Tuning;
+use crate::{Enemy, EnemyType, Player, WaveManager, WaveState};
+
+const BASE_DIVE_INTERVAL: f32 = 3.5; // Au niveau 1, un plongeon toutes les 3,5 secondes.
+const DIVE_SPEED_FACTOR: f32 = 2.6; // Un alien en piqué va 2,6 fois plus vite que la vitesse de base.
+const EXIT_MARGIN: f32 = 40.0; // On sort un peu sous le bas de l'écran avant de réapparaître en haut.
+const TRACTOR_CHANCE: f32 = 0.5; // Une chance sur deux qu'un chef plonge pour capturer au lieu de foncer.
+
+#[derive(Clone, Copy)]
+enum Anchor { // Par rapport à quoi un point de la trajectoire est posé.
+    Start, // Par rapport à la place que l'alien vient de quitter.
+    Player, // Par rapport au vaisseau du joueur (au moment du départ).
+    Exit, // Sous le bas de l'écran, à la verticale du joueur.
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DivePattern { Loop, Swoop, Strafe } // Les trois attaques de la formation.
+
+// Les trajectoires sont dessinées pour un alien qui part vers la droite : on les retourne en miroir pour la gauche.
+const LOOP_PATH: [(Anchor, Vec2); 9] = [ // Il fait un looping puis pique sur le joueur.
+    (Anchor::Start, Vec2::new(0.0, 0.0)),
+    (Anchor::Start, Vec2::new(30.0, 50.0)),
+    (Anchor::Start, Vec2::new(90.0, 40.0)),
+    (Anchor::Start, Vec2::new(110.0, -20.0)),
+    (Anchor::Start, Vec2::new(70.0, -70.0)),
+    (Anchor::Start, Vec2::new(20.0, -40.0)),
+    (Anchor::Player, Vec2::new(40.0, 220.0)),
+    (Anchor::Player, Vec2::new(0.0, 60.0)),
+    (Anchor::Exit, Vec2::new(-40.0, 0.0)),
+];
+const SWOOP_PATH: [(Anchor, Vec2); 10] = [ // Un grand virage en Bézier qui fonce à travers l'écran.
+    (Anchor::Start, Vec2::new(0.0, 0.0)),
+    (Anchor::Start, Vec2::new(0.0, 80.0)),
+    (Anchor::Start, Vec2::new(160.0, 60.0)),
+    (Anchor::Start, Vec2::new(140.0, -80.0)),
+    (Anchor::Start, Vec2::new(120.0, -220.0)),
+    (Anchor::Player, Vec2::new(120.0, 200.0)),
+    (Anchor::Player, Vec2::new(0.0, 40.0)),
+    (Anchor::Player, Vec2::new(-120.0, -120.0)),
+    (Anchor::Exit, Vec2::new(-40.0, 80.0)),
+    (Anchor::Exit, Vec2::new(-60.0, 0.0)),
+];
+const STRAFE_PATH: [(Anchor, Vec2); 7] = [ // Il descend, rase le joueur à l'horizontale, puis remonte à sa place.
+    (Anchor::Start, Vec2::new(0.0, 0.0)),
+    (Anchor::Start, Vec2::new(40.0, 40.0)),
+    (Anchor::Start, Vec2::new(80.0, -30.0)),
+    (Anchor::Player, Vec2::new(160.0, 140.0)),
+    (Anchor::Player, Vec2::new(0.0, 110.0)),
+    (Anchor::Player, Vec2::new(-180.0, 110.0)),
+    (Anchor::Player, Vec2::new(-220.0, 260.0)),
+];
+
+impl DivePattern {
+    fn build(self, start: Vec2, player: Vec2, exit_y: f32, side: f32) -> Spline { // On transforme le dessin en vraie courbe à l'écran.
+        let points: &[(Anchor, Vec2)] = match self {
+            DivePattern::Loop => &LOOP_PATH,
+            DivePattern::Swoop => &SWOOP_PATH,
+            DivePattern::Strafe => &STRAFE_PATH,
+        };
+        let resolved = points.iter().map(|(anchor, offset)| {
+            let offset = Vec2::new(offset.x * side, offset.y); // Le miroir gauche/droite.
+            match anchor {
+                Anchor::Start => start + offset,
+                Anchor::Player => player + offset,
+                Anchor::Exit => Vec2::new(player.x, exit_y) + offset,
+            }
+        }).collect();
+        match self {
+            DivePattern::Swoop => Spline::Bezier(resolved),
+            DivePattern::Loop | DivePattern::Strafe => Spline::CatmullRom(resolved),
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct DiveScheduler { timer: Timer } // Le chrono qui décide quand un alien part en piqué.
+
+impl Default for DiveScheduler {
+    fn default() -> Self { Self { timer: Timer::from_seconds(BASE_DIVE_INTERVAL, TimerMode::Repeating) } }
+}
+
+pub fn difficulty(level: u32, wave: u32) -> f32 { // 1.0 au tout début, puis ça monte à chaque vague et chaque niveau.
+    1.0 + 0.35 * level.saturating_sub(1) as f32 + 0.08 * wave.saturating_sub(1) as f32
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn dive_scheduler_system( // On choisit qui part en piqué.
+    mut commands: Commands,
+    time: Res<Time>,
+    tuning: Res<Tuning>,
+    mut rng: ResMut<GameRng>,
+    mut scheduler: ResMut<DiveScheduler>,
+    wave_mgr: Res<WaveManager>,
+    lives: Res<Lives>,
+    playfield: Res<Playfield>,
+    player_q: Query<(&Transform, Has<DualFighter>, Has<BeingCaptured>), With<Player>>,
+    enemy_q: Query<(Entity, &Enemy, &Transform, &Flight)>,
+    capture_q: Query<(), Or<(With<CapturedFighter>, With<TractorDive>)>>,
+) {
+    if wave_mgr.state != WaveState::Fighting { return; } // On attend que toute la vague soit arrivée.
+    if lives.ship_down() { return; } // Le vaisseau vient d'exploser : la formation se regroupe au lieu d'attaquer.
+    let difficulty = difficulty(wave_mgr.current_level, wave_mgr.current_wave);
+    scheduler.timer.set_duration(Duration::from_secs_f32(BASE_DIVE_INTERVAL / difficulty)); // Plus c'est dur, plus c'est souvent.
+    scheduler.timer.tick(time.delta());
+    if !scheduler.timer.just_finished() { return; }
+
+    let dive_speed = tuning.enemy_speed * DIVE_SPEED_FACTOR;
+    let max_divers = 1 + difficulty as usize; // Combien d'aliens peuvent plonger en même temps.
+    if enemy_q.iter().filter(|(_, _, _, f)| matches!(f, Flight::Diving)).count() >= max_divers { return; }
+    let candidates: Vec<_> = enemy_q.iter().filter(|(_, _, _, f)| matches!(f, Flight::InFormation)).collect();
+    if candidates.is_empty() { return; }
+    let (entity, enemy, trans, _) = candidates[rng.ai.gen_range(0..candidates.len())]; // On tire au sort le plongeur.
+
+    let start = trans.translation.xy();
+    let player = player_q.single().ok();
+    let side = if start.x < 0.0 { -1.0 } else { 1.0 }; // On part d'abord vers le bord le plus proche.
+    if enemy.kind == EnemyType::Boss && capture_q.is_empty() && rng.ai.gen::<f32>() < TRACTOR_CHANCE { // Un chef peut venir capturer le joueur.
+        if let Some((p_trans, false, false)) = player { // Seulement un joueur seul et libre.
+            let spline = tractor::tractor_path(start, p_trans.translation.xy(), side);
+            commands.entity(entity).insert((Flight::Diving, TractorDive, PathFollower::new(spline, dive_speed)));
+            return;
+        }
+    }
+
+    let pattern = if enemy.kind == EnemyType::Boss { DivePattern::Loop } else { // Le chef fait toujours son looping.
+        match rng.ai.gen_range(0..3) { 0 => DivePattern::Loop, 1 => DivePattern::Swoop, _ => DivePattern::Strafe }
+    };
+    let player = player.map(|(t, ..)| t.translation.xy()).unwrap_or(Vec2::new(0.0, -300.0));
+    let spline = pattern.build(start, player, -playfield.height() / 2.0 - EXIT_MARGIN, side);
+    commands.entity(entity).insert((Flight::Diving, PathFollower::new(spline, dive_speed * (0.85 + 0.15 * difficulty))));
+}
+
+#[allow(clippy::type_complexity)]
+pub fn dive_end_system( // Quand le plongeon est fini, l'alien rentre à la maison.
+    mut commands: Commands,
+    formation: Res<Formation>,
+    playfield: Res<Playfield>,
+    mut query: Query<(Entity, &PathFollower, &FormationSlot, &mut Flight, &mut Transform), (With<Enemy>, Without<TractorDive>)>,
+) {
+    for (entity, follower, slot, mut flight, mut trans) in query.iter_mut() {
+        if !follower.finished() { continue; }
+        commands.entity(entity).remove::<PathFollower>();
+        let home = formation.slot_position(slot);
+        let mut start = trans.translation.xy();
+        if start.y < -playfield.height() / 2.0 { // Il est sorti par le bas : il réapparaît en haut, au-dessus de sa place.
+            start = Vec2::new(home.x, playfield.height() / 2.0 + EXIT_MARGIN);
+            trans.translation = start.extend(trans.translation.z);
+        }
+        *flight = Flight::Entering { start, control: (start + home) / 2.0 + Vec2::new(0.0, 80.0), progress: 0.0 };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn left_dives_mirror_right_dives() { // Un alien et le joueur au milieu : les deux côtés sont symétriques.
+        let (start, player, exit_y) = (Vec2::new(0.0, 200.0), Vec2::new(0.0, -300.0), -400.0);
+        for pattern in [DivePattern::Loop, DivePattern::Swoop, DivePattern::Strafe] {
+            let (right, left) = (pattern.build(start, player, exit_y, 1.0), pattern.build(start, player, exit_y, -1.0));
+            assert!(right.sample(0.0).distance(start) < 1e-3, "{:?} part de sa place", pattern);
+            for i in 0..=20 {
+                let (r, l) = (right.sample(i as f32 / 20.0), left.sample(i as f32 / 20.0));
+                assert!(l.distance(Vec2::new(-r.x, r.y)) < 1e-3, "{:?} à t = {} : {} et {}", pattern, i as f32 / 20.0, r, l);
+            }
+        }
+    }
+}
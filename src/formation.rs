@@ -0,0 +1,213 @@
+// ═══════════════════════════════════════════════════════════════════════════
+// 🛸 LA FORMATION : la grande grille où les aliens viennent se ranger
+// ═══════════════════════════════════════════════════════════════════════════
+//
+// Comme dans Galaga, chaque alien reçoit une place (un "slot") dans une grille
+// accrochée en haut de l'écran. Il arrive en faisant une belle courbe, puis il
+// reste à sa place : la grille se balance de gauche à droite pendant que la
+// vague arrive, puis elle "respire" (elle s'écarte et se resserre) quand tout
+// le monde est arrivé. Comme à l'arcade, elle continue de respirer pendant que
+// des aliens partent en piqué : seule une nouvelle vague la refait balancer.
+
+use bevy::prelude::*;
+
+use crate::levels::EntryStyle;
+use crate::paths::quadratic_bezier;
+use crate::playfield::Playfield;
+use crate::{Enemy, SpawnDirection, WaveManager, WaveState};
+
+const ROW_WIDTHS: [u32; 5] = [4, 8, 8, 10, 10]; // Nombre de places par rangée : 4 chefs, puis 8, 8, 10 et 10 soldats.
+const SLOT_SPACING: Vec2 = Vec2::new(50.0, 40.0); // L'écart entre deux places de la grille.
+const TOP_MARGIN: f32 = 110.0; // La distance entre le haut de l'écran et la rangée des chefs.
+const SWAY_AMPLITUDE: f32 = 40.0; // De combien de pixels la grille se balance.
+const SWAY_SPEED: f32 = 1.2; // La vitesse du balancement.
+const BREATH_AMPLITUDE: f32 = 0.18; // De combien la grille s'écarte quand elle respire (18%).
+const BREATH_SPEED: f32 = 2.0; // La vitesse de la respiration.
+const BREATH_BLEND_SPEED: f32 = 0.5; // Le temps pour passer doucement du balancement à la respiration.
+const ENTRY_DURATION: f32 = 2.2; // Le temps (en secondes) que met un alien pour rejoindre sa place.
+
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub struct FormationSlot { pub col: u32, pub row: u32 } // La place de l'alien dans la grille.
+
+#[derive(Component, Clone, Copy, Debug)]
+pub enum Flight { // Ce que l'alien est en train de faire.
+    Entering { start: Vec2, control: Vec2, progress: f32 }, // Il vole vers sa place en suivant une courbe.
+    InFormation, // Il est rangé à sa place.
+    Diving, // Il a quitté la grille pour attaquer (c'est son `PathFollower` qui le fait bouger).
+}
+
+#[derive(Resource)]
+pub struct Formation { // La grille partagée par tous les aliens de la vague.
+    pub anchor: Vec2, // Le centre de la rangée du haut.
+    spacing: Vec2, // L'écart entre deux places.
+    phase: f32, // Le temps qui passe, pour le balancement et la respiration.
+    breathing: f32, // 0 = on se balance, 1 = on respire (entre les deux, on mélange).
+    arrived: bool, // Toute la vague est arrivée une fois : on respire jusqu'à la vague suivante.
+    bosses_assigned: u32, // Combien de places de chef sont déjà prises.
+    soldiers_assigned: u32, // Combien de places de soldat sont déjà prises.
+}
+
+impl Default for Formation {
+    fn default() -> Self {
+        Self {
+            anchor: Vec2::new(0.0, 250.0), // Une position de secours tant qu'on ne connaît pas la fenêtre.
+            spacing: SLOT_SPACING,
+            phase: 0.0,
+            breathing: 0.0,
+            arrived: false,
+            bosses_assigned: 0,
+            soldiers_assigned: 0,
+        }
+    }
+}
+
+impl Formation {
+    pub fn reset_slots(&mut self) { // On libère toutes les places pour une nouvelle vague.
+        self.bosses_assigned = 0;
+        self.soldiers_assigned = 0;
+        self.breathing = 0.0;
+        self.arrived = false;
+    }
+
+    pub fn reserve_slot(&mut self, is_boss: bool) -> FormationSlot { // On donne la prochaine place libre.
+        if is_boss { // Les chefs vont dans la rangée du haut.
+            let index = self.bosses_assigned % ROW_WIDTHS[0];
+            self.bosses_assigned += 1;
+            return FormationSlot { col: center_out(ROW_WIDTHS[0], index), row: 0 };
+        }
+        let soldier_capacity: u32 = ROW_WIDTHS[1..].iter().sum(); // Les soldats remplissent les autres rangées.
+        let mut index = self.soldiers_assigned % soldier_capacity;
+        self.soldiers_assigned += 1;
+        for (row, width) in ROW_WIDTHS.iter().enumerate().skip(1) { // On cherche la rangée qui contient cette place.
+            if index < *width { return FormationSlot { col: center_out(*width, index), row: row as u32 }; }
+            index -= width;
+        }
+        FormationSlot { col: 0, row: 1 } // On n'arrive jamais ici, mais il faut bien répondre quelque chose.
+    }
+
+    pub fn slot_position(&self, slot: &FormationSlot) -> Vec2 { // Où se trouve une place en ce moment ?
+        let width = ROW_WIDTHS[slot.row as usize] as f32;
+        let local = Vec2::new( // La position de la place par rapport au centre de la rangée du haut.
+            (slot.col as f32 - (width - 1.0) / 2.0) * self.spacing.x,
+            -(slot.row as f32) * self.spacing.y,
+        );
+        let spread = 1.0 + BREATH_AMPLITUDE * self.breathing * (0.5 - 0.5 * (self.phase * BREATH_SPEED).cos()); // La respiration écarte les places.
+        let sway = SWAY_AMPLITUDE * (1.0 - self.breathing) * (self.phase * SWAY_SPEED).sin(); // Le balancement pousse toute la grille.
+        self.anchor + local * spread + Vec2::new(sway, 0.0)
+    }
+}
+
+fn center_out(width: u32, index: u32) -> u32 { // On remplit une rangée en partant du milieu vers les bords.
+    let mut cols: Vec<u32> = (0..width).collect();
+    cols.sort_by_key(|c| ((2 * *c as i32 - (width as i32 - 1)).abs(), *c));
+    cols[index as usize]
+}
+
+pub fn capacity() -> (usize, usize) { // Combien de chefs et combien de soldats la grille peut ranger.
+    (ROW_WIDTHS[0] as usize, ROW_WIDTHS[1..].iter().sum::<u32>() as usize)
+}
+
+pub fn entry_flight(entry: EntryStyle, direction: SpawnDirection, start: Vec2) -> Flight { // La courbe d'arrivée selon le style et le côté d'où vient l'alien.
+    Flight::Entering { start, control: entry.control_point(direction, start), progress: 0.0 }
+}
+
+pub fn formation_system( // La grille se balance ou respire.
+    time: Res<Time>,
+    mut formation: ResMut<Formation>,
+    wave_mgr: Res<WaveManager>,
+    flight_q: Query<&Flight, With<Enemy>>,
+    playfield: Res<Playfield>,
+) {
+    formation.anchor = Vec2::new(0.0, playfield.height() / 2.0 - TOP_MARGIN); // On accroche la grille en haut de l'écran.
+    let dt = time.delta_secs();
+    formation.phase += dt;
+    if wave_mgr.state == WaveState::Fighting && !flight_q.iter().any(|f| matches!(f, Flight::Entering { .. })) { formation.arrived = true; } // Plus personne en route : la vague est là.
+    let target = if formation.arrived { 1.0 } else { 0.0 };
+    formation.breathing = if formation.breathing < target { (formation.breathing + dt * BREATH_BLEND_SPEED).min(target) } else { (formation.breathing - dt * BREATH_BLEND_SPEED).max(target) };
+}
+
+pub fn enemy_flight_system( // Les aliens rejoignent leur place puis la suivent.
+    time: Res<Time>,
+    formation: Res<Formation>,
+    mut query: Query<(&FormationSlot, &mut Flight, &mut Transform), With<Enemy>>,
+) {
+    for (slot, mut flight, mut trans) in query.iter_mut() {
+        let target = formation.slot_position(slot); // La place bouge avec la grille, on la recalcule à chaque fois.
+        let pos = match *flight {
+            Flight::Entering { start, control, progress } => {
+                let progress = (progress + time.delta_secs() / ENTRY_DURATION).min(1.0);
+                *flight = if progress >= 1.0 { Flight::InFormation } else { Flight::Entering { start, control, progress } };
+                quadratic_bezier(start, control, target, progress)
+            }
+            Flight::InFormation => target,
+            Flight::Diving => continue, // Un plongeur n'écoute plus la grille.
+        };
+        trans.translation = pos.extend(trans.translation.z);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tuning::Tuning;
+    use crate::EnemyType;
+
+    #[test]
+    fn the_grid_holds_every_row() {
+        let (bosses, soldiers) = capacity();
+        assert_eq!(bosses, ROW_WIDTHS[0] as usize);
+        assert_eq!(bosses + soldiers, ROW_WIDTHS.iter().sum::<u32>() as usize);
+    }
+
+    #[test]
+    fn slots_fill_from_the_middle_and_wrap_around() {
+        let mut formation = Formation::default();
+        let bosses: Vec<u32> = (0..4).map(|_| formation.reserve_slot(true).col).collect();
+        assert_eq!(bosses, vec![1, 2, 0, 3], "le milieu d'abord, puis les bords");
+        assert_eq!(formation.reserve_slot(true), FormationSlot { col: 1, row: 0 }, "une rangée pleine recommence");
+        let soldiers: Vec<FormationSlot> = (0..capacity().1).map(|_| formation.reserve_slot(false)).collect();
+        assert!(soldiers[..8].iter().all(|slot| slot.row == 1) && soldiers[8..16].iter().all(|slot| slot.row == 2));
+        assert_eq!(soldiers.last(), Some(&FormationSlot { col: 9, row: 4 }), "le dernier coin de la dernière rangée");
+        formation.reset_slots();
+        assert_eq!(formation.reserve_slot(false), soldiers[0]);
+    }
+
+    #[test]
+    fn slots_are_spaced_around_the_anchor() {
+        let formation = Formation::default(); // Au repos : ni balancement, ni respiration.
+        let left = formation.slot_position(&FormationSlot { col: 0, row: 0 });
+        let right = formation.slot_position(&FormationSlot { col: 3, row: 0 });
+        assert_eq!((left + right) / 2.0, formation.anchor, "la rangée des chefs est centrée");
+        assert_eq!(right.x - left.x, 3.0 * SLOT_SPACING.x);
+        assert_eq!(formation.slot_position(&FormationSlot { col: 0, row: 2 }).y, formation.anchor.y - 2.0 * SLOT_SPACING.y);
+    }
+
+    fn breathing_after(world: &mut World, seconds: f32) -> f32 { // On fait tourner la grille pendant `seconds`.
+        for _ in 0..(seconds * 10.0) as u32 {
+            world.resource_mut::<Time>().advance_by(std::time::Duration::from_millis(100));
+            world.run_system_cached(formation_system).expect("formation_system");
+        }
+        world.resource::<Formation>().breathing
+    }
+
+    #[test]
+    fn the_grid_keeps_breathing_while_aliens_dive() {
+        let mut world = World::new();
+        let mut wave_mgr = WaveManager::new(&Tuning::default());
+        wave_mgr.state = WaveState::Fighting;
+        world.insert_resource(wave_mgr);
+        world.init_resource::<Time>();
+        world.init_resource::<Formation>();
+        world.init_resource::<Playfield>();
+        let entering = Flight::Entering { start: Vec2::ZERO, control: Vec2::ZERO, progress: 0.5 };
+        let aliens: Vec<Entity> = (0..3).map(|_| world.spawn((Enemy::new(EnemyType::Soldier), entering)).id()).collect();
+        assert_eq!(breathing_after(&mut world, 3.0), 0.0, "la vague arrive : on se balance");
+        for &alien in &aliens { world.entity_mut(alien).insert(Flight::InFormation); }
+        assert_eq!(breathing_after(&mut world, 3.0), 1.0);
+        world.entity_mut(aliens[0]).insert(Flight::Diving);
+        assert_eq!(breathing_after(&mut world, 3.0), 1.0, "un plongeur ne fait pas changer la grille");
+        world.resource_mut::<Formation>().reset_slots();
+        world.entity_mut(aliens[1]).insert(entering); // La vague suivante arrive.
+        assert_eq!(breathing_after(&mut world, 3.0), 0.0);
+    }
+}
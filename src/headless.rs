@@ -0,0 +1,150 @@
+// ═══════════════════════════════════════════════════════════════════════════
+// 🤖 LE MODE SANS FENÊTRE : le jeu tourne tout seul, aussi vite que possible
+// ═══════════════════════════════════════════════════════════════════════════
+//
+// Pour vérifier les vagues, le score et la fin de partie sur une machine sans
+// carte graphique, on lance le jeu sans fenêtre ni dessin :
+//
+//   cargo run -- --headless --frames 36000 --seed 7
+//   cargo run -- --headless --replay partie.xgr
+//   cargo run --release -- --bench-collisions
+//
+// Chaque `app.update()` fait exactement un pas de simulation, sans attendre
+// l'horloge : des milliers de pas par seconde. Les tests construisent leur
+// application de la même façon.
+//
+// La mesure des chocs ne lance pas de partie : elle remplit un monde de 2 000
+// balles et 200 aliens qui bougent, et chronomètre la passe de détection à
+// chaque pas, avec la grille et avec la passe naïve (tout contre tout).
+
+use std::time::{Duration, Instant};
+
+use bevy::app::{PluginGroup, PluginGroupBuilder};
+use bevy::asset::AssetPlugin;
+use bevy::image::{ImagePlugin, TextureAtlasPlugin};
+use bevy::input::InputPlugin;
+use bevy::prelude::*;
+use bevy::state::app::StatesPlugin;
+use bevy::time::TimeUpdateStrategy;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::collider::{self, Collider, Contacts};
+use crate::interpolation::SIMULATION_HZ;
+use crate::playfield;
+use crate::rng::GameRng;
+use crate::lives::Lives;
+use crate::stats::GameStats;
+use crate::tuning::Tuning;
+use crate::{AppState, EnemyType, GameState, Movable, WaveManager};
+
+const LOAD_TIMEOUT: Duration = Duration::from_secs(10); // Le temps maximum pour charger les fichiers du jeu.
+const BENCH_BULLETS: usize = 2_000; // La moitié du joueur (qui montent), la moitié des aliens (qui descendent).
+const BENCH_ENEMIES: usize = 200;
+
+pub struct HeadlessPlugins; // Juste ce qu'il faut pour simuler : pas de fenêtre, pas de dessin, pas de son.
+
+impl PluginGroup for HeadlessPlugins {
+    fn build(self) -> PluginGroupBuilder {
+        PluginGroupBuilder::start::<Self>()
+            .add_group(MinimalPlugins)
+            .add(AssetPlugin { watch_for_changes_override: Some(false), ..default() }) // Pas de rechargement à chaud ici.
+            .add(ImagePlugin::default()) // Les images sont demandées par les systèmes, même si personne ne les dessine.
+            .add(TextureAtlasPlugin) // Pareil pour les planches des animations.
+            .add(InputPlugin)
+            .add(StatesPlugin)
+    }
+}
+
+pub fn configure(app: &mut App) { // Un `update()` = un pas de simulation, sans attendre l'horloge.
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(1.0 / SIMULATION_HZ)));
+}
+
+pub fn wait_until_loaded(app: &mut App) -> bool { // On fait tourner l'application jusqu'à la fin du chargement, puis on passe au premier écran.
+    app.finish();
+    app.cleanup();
+    let start = Instant::now();
+    while start.elapsed() < LOAD_TIMEOUT {
+        app.update();
+        if matches!(app.world().resource::<NextState<AppState>>(), NextState::Pending(_)) { // Le chargement est fini.
+            app.world_mut().run_schedule(StateTransition); // On entre dans la partie tout de suite : le premier pas compté sera son premier pas.
+            return true;
+        }
+        std::thread::sleep(Duration::from_millis(1));
+    }
+    false
+}
+
+pub fn run(mut app: App, frames: u64) -> AppExit { // On simule `frames` pas (ou jusqu'à la fin de la partie) et on affiche le bilan.
+    if !wait_until_loaded(&mut app) {
+        eprintln!("Les fichiers du jeu ne se chargent pas (assets/levels, assets/tuning.tuning.ron).");
+        return AppExit::error();
+    }
+    let start = Instant::now();
+    let mut ticks = 0;
+    while ticks < frames {
+        app.update();
+        ticks += 1;
+        if matches!(app.world().resource::<State<AppState>>().get(), AppState::GameOver | AppState::Victory) { break; } // La partie est finie.
+        if let Some(exit) = app.should_exit() { return exit; }
+    }
+    let elapsed = start.elapsed().as_secs_f64();
+
+    let world = app.world_mut();
+    let lives = world.resource::<Lives>().ships;
+    let (state, wave_mgr, seed) = (world.resource::<GameState>(), world.resource::<WaveManager>(), world.resource::<GameRng>().seed);
+    let outcome = match world.resource::<State<AppState>>().get() { AppState::Victory => "VICTOIRE", AppState::GameOver => "GAME OVER", _ => "en cours" };
+    println!("Graine {} : {} pas simulés en {:.2} s ({:.0} pas/s)", seed, ticks, elapsed, ticks as f64 / elapsed.max(1e-9));
+    println!("Partie {} : score {}, niveau {} vague {} ({:?}), vies {}", outcome, state.score, wave_mgr.current_level, wave_mgr.current_wave, wave_mgr.state, lives);
+    println!("Statistiques : {}", world.resource::<GameStats>().summary());
+    AppExit::Success
+}
+
+pub fn bench_collisions(seed: u64, frames: u64) -> AppExit { // On chronomètre la détection des chocs, pas par pas.
+    let tuning = Tuning::default();
+    let half = playfield::DEFAULT_SIZE / 2.0;
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut world = World::new();
+    world.init_resource::<Contacts>();
+    let place = |world: &mut World, collider: Collider, pos: Vec2, velocity: Vec2| { world.spawn((collider, Movable { velocity }, Transform::from_translation(pos.extend(0.0)))); };
+    place(&mut world, Collider::player(&tuning, false), Vec2::new(0.0, -300.0), Vec2::ZERO);
+    for i in 0..BENCH_ENEMIES {
+        let kind = if i % 10 == 0 { EnemyType::Boss } else { EnemyType::Soldier };
+        let pos = Vec2::new(rng.gen_range(-half.x..half.x), rng.gen_range(-half.y * 0.2..half.y));
+        place(&mut world, Collider::enemy(&tuning, kind), pos, Vec2::new(rng.gen_range(-60.0..60.0), rng.gen_range(-60.0..60.0)));
+    }
+    for i in 0..BENCH_BULLETS {
+        let from_player = i % 2 == 0;
+        let pos = Vec2::new(rng.gen_range(-half.x..half.x), rng.gen_range(-half.y..half.y));
+        let velocity = if from_player { Vec2::new(0.0, tuning.bullet_speed) } else { Vec2::new(rng.gen_range(-80.0..80.0), -tuning.enemy_speed * 1.8) };
+        place(&mut world, Collider::bullet(&tuning, from_player), pos, velocity);
+    }
+
+    let dt = 1.0 / SIMULATION_HZ as f32;
+    let (mut grid_total, mut grid_worst, mut naive_total, mut contacts) = (Duration::ZERO, Duration::ZERO, Duration::ZERO, 0);
+    for _ in 0..frames {
+        for (mut trans, movable) in world.query::<(&mut Transform, &Movable)>().iter_mut(&mut world) { // Tout bouge, et revient de l'autre côté en sortant.
+            let pos = trans.translation.xy() + movable.velocity * dt;
+            trans.translation = ((pos + half).rem_euclid(half * 2.0) - half).extend(0.0);
+        }
+        let start = Instant::now();
+        world.run_system_cached(collider::detect_contacts_system).expect("détection des chocs");
+        let elapsed = start.elapsed();
+        grid_total += elapsed;
+        grid_worst = grid_worst.max(elapsed);
+        contacts += world.resource::<Contacts>().0.len();
+
+        let colliders: Vec<(Entity, Vec2, Collider)> = world.query::<(Entity, &Transform, &Collider)>().iter(&world).map(|(e, t, c)| (e, t.translation.xy(), *c)).collect();
+        let start = Instant::now();
+        let naive = collider::find_contacts_naive(&colliders);
+        naive_total += start.elapsed();
+        assert_eq!(naive.len(), world.resource::<Contacts>().0.len(), "la grille et la passe naïve ne trouvent pas les mêmes chocs");
+    }
+    let per_frame = |total: Duration| total.as_secs_f64() * 1000.0 / frames.max(1) as f64;
+    println!("Chocs : {} balles, {} aliens, {} pas (graine {})", BENCH_BULLETS, BENCH_ENEMIES, frames, seed);
+    println!("  grille      : {:.3} ms par pas en moyenne, {:.3} ms au pire", per_frame(grid_total), grid_worst.as_secs_f64() * 1000.0);
+    println!("  passe naïve : {:.3} ms par pas en moyenne", per_frame(naive_total));
+    println!("  {:.1} paires en contact par pas", contacts as f64 / frames.max(1) as f64);
+    AppExit::Success
+}
@@ -0,0 +1,242 @@
+// ═══════════════════════════════════════════════════════════════════════════
+// 🏆 LE TABLEAU DES SCORES : les 10 meilleures parties, gardées d'une fois sur l'autre
+// ═══════════════════════════════════════════════════════════════════════════
+//
+// Après "GAME OVER", si ton score entre dans les 10 meilleurs, tu tapes tes
+// trois initiales comme sur la borne d'arcade : flèches gauche/droite pour
+// choisir la lettre, Espace pour la valider. Le tableau est écrit dans le
+// dossier de données de l'utilisateur :
+//
+//   Linux    ~/.local/share/xgalaga-rust/highscores.ron  (ou $XDG_DATA_HOME)
+//   macOS    ~/Library/Application Support/xgalaga-rust/highscores.ron
+//   Windows  %APPDATA%\xgalaga-rust\highscores.ron
+//
+// Si le fichier manque, on part d'un tableau vide. S'il est abîmé, on le met de
+// côté (highscores.ron.bak) et on repart à zéro au lieu de planter. Sans
+// fenêtre (tests, simulations) et pendant un replay, rien n'est écrit.
+
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::input::PlayerInput;
+use crate::levels;
+
+pub const TABLE_SIZE: usize = 10; // On garde les 10 meilleures parties.
+pub const INITIALS_LEN: usize = 3; // Trois lettres, comme à l'arcade.
+const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ."; // Les caractères qu'on peut choisir pour ses initiales.
+const APP_DIR: &str = "xgalaga-rust"; // Le dossier du jeu dans le dossier de données.
+const FILE_NAME: &str = "highscores.ron";
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct HighScore { // Une ligne du tableau.
+    pub initials: String, // "ABC".
+    pub score: u32,
+    pub level: u32, // Le niveau atteint.
+    pub wave: u32, // La vague atteinte dans ce niveau.
+    pub date: String, // Le jour de la partie, "AAAA-MM-JJ".
+}
+
+#[derive(Resource, Default)]
+pub struct HighScores { pub entries: Vec<HighScore>, path: Option<PathBuf> } // Les meilleures parties (la meilleure d'abord), et le fichier où les écrire.
+
+impl HighScores {
+    pub fn load(path: PathBuf) -> (Self, Option<String>) { // On lit le fichier ; s'il y a un problème, on dit lequel.
+        let text = match std::fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return (Self { entries: Vec::new(), path: Some(path) }, None), // Première partie : pas encore de tableau.
+            Err(err) => return (Self::default(), Some(format!("Tableau des scores illisible ({}) : {} ; les scores de cette session ne seront pas enregistrés", path.display(), err))), // On n'écrase pas un fichier qu'on n'a pas pu lire.
+        };
+        match levels::parse::<Vec<HighScore>>(&text, &path.display().to_string()) {
+            Ok(entries) => (Self::from_entries(entries, Some(path)), None),
+            Err(err) => { // Fichier abîmé : on le garde de côté pour ne pas l'écraser, et on repart à zéro.
+                let backup = path.with_extension("ron.bak");
+                let kept = std::fs::rename(&path, &backup).is_ok();
+                let note = if kept { format!(", copie gardée dans {}", backup.display()) } else { " ; les scores de cette session ne seront pas enregistrés".into() };
+                (Self { entries: Vec::new(), path: kept.then_some(path) }, Some(format!("Tableau des scores abîmé : {}{}", err, note))) // Sans copie, on ne l'écrase pas.
+            }
+        }
+    }
+
+    fn from_entries(mut entries: Vec<HighScore>, path: Option<PathBuf>) -> Self { // On remet en ordre ce qu'on a lu (quelqu'un a pu modifier le fichier à la main).
+        for entry in entries.iter_mut() { entry.initials = clean_initials(&entry.initials); }
+        entries.sort_by_key(|e| std::cmp::Reverse(e.score));
+        entries.truncate(TABLE_SIZE);
+        Self { entries, path }
+    }
+
+    pub fn best(&self) -> u32 { self.entries.first().map(|e| e.score).unwrap_or(0) } // Le record à battre.
+
+    pub fn qualifies(&self, score: u32) -> bool { // Ce score entre-t-il dans le tableau ?
+        score > 0 && (self.entries.len() < TABLE_SIZE || self.entries.last().is_some_and(|e| score > e.score))
+    }
+
+    pub fn insert(&mut self, entry: HighScore) -> usize { // On range la partie à sa place ; à égalité, l'ancienne reste devant.
+        let rank = self.entries.iter().position(|e| entry.score > e.score).unwrap_or(self.entries.len());
+        self.entries.insert(rank, entry);
+        self.entries.truncate(TABLE_SIZE);
+        rank
+    }
+
+    pub fn save(&self) -> Result<(), String> { // On écrit le tableau (d'abord dans un fichier à côté, pour ne jamais laisser un fichier à moitié écrit).
+        let Some(path) = &self.path else { return Ok(()) }; // Tableau en mémoire seulement.
+        let text = ron::ser::to_string_pretty(&self.entries, ron::ser::PrettyConfig::default()).map_err(|err| err.to_string())?;
+        let write = |path: &Path| -> std::io::Result<()> {
+            if let Some(dir) = path.parent() { std::fs::create_dir_all(dir)?; }
+            let tmp = path.with_extension("ron.tmp");
+            std::fs::write(&tmp, text)?;
+            std::fs::rename(&tmp, path)
+        };
+        write(path).map_err(|err| format!("{} : {}", path.display(), err))
+    }
+}
+
+pub fn default_path() -> Option<PathBuf> { data_dir().map(|dir| dir.join(APP_DIR).join(FILE_NAME)) } // Où ranger le tableau sur cet ordinateur.
+
+fn data_dir() -> Option<PathBuf> { // Le dossier de données de l'utilisateur, selon le système.
+    let var = |name: &str| std::env::var_os(name).filter(|v| !v.is_empty()).map(PathBuf::from);
+    if cfg!(windows) { return var("APPDATA"); }
+    if cfg!(target_os = "macos") { return var("HOME").map(|home| home.join("Library/Application Support")); }
+    var("XDG_DATA_HOME").or_else(|| var("HOME").map(|home| home.join(".local/share")))
+}
+
+fn clean_initials(text: &str) -> String { // Trois caractères de l'alphabet de la borne, en majuscules.
+    let mut letters: Vec<u8> = text.to_uppercase().bytes().filter(|b| ALPHABET.contains(b)).take(INITIALS_LEN).collect();
+    letters.resize(INITIALS_LEN, b'.');
+    String::from_utf8(letters).expect("l'alphabet est en ASCII")
+}
+
+pub fn today() -> String { // La date du jour (UTC), sans bibliothèque de dates.
+    let days = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs() / 86_400).unwrap_or(0) as i64;
+    let (year, month, day) = civil_from_days(days);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+fn civil_from_days(days: i64) -> (i64, u32, u32) { // Jours depuis le 1er janvier 1970 -> (année, mois, jour), calendrier grégorien.
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097; // Le jour dans le cycle de 400 ans.
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100); // Le jour dans l'année (qui commence en mars).
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    (yoe + era * 400 + i64::from(month <= 2), month, day)
+}
+
+#[derive(Resource, Default)]
+pub struct InitialsEntry { // Les initiales en train d'être tapées.
+    letters: [u8; INITIALS_LEN], // Les positions dans `ALPHABET`.
+    cursor: usize, // La lettre qu'on est en train de choisir.
+    held: (bool, bool), // Gauche et droite au pas d'avant : une lettre par appui, pas une par pas.
+    pub active: bool, // Vrai tant que le joueur tape ses initiales.
+    pub rank: Option<usize>, // La place de la partie dans le tableau, une fois enregistrée.
+}
+
+impl InitialsEntry {
+    pub fn begin(&mut self, active: bool) { *self = Self { active, ..default() }; } // On commence sur "AAA" (ou on n'entre rien).
+
+    pub fn update(&mut self, input: &PlayerInput) -> bool { // Un pas de saisie ; vrai quand la dernière lettre est validée.
+        let n = ALPHABET.len() as u8;
+        let letter = &mut self.letters[self.cursor];
+        if input.left && !self.held.0 { *letter = (*letter + n - 1) % n; }
+        if input.right && !self.held.1 { *letter = (*letter + 1) % n; }
+        self.held = (input.left, input.right);
+        if input.fire { self.cursor += 1; }
+        self.cursor >= INITIALS_LEN
+    }
+
+    pub fn initials(&self) -> String { self.letters.iter().map(|&i| ALPHABET[i as usize] as char).collect() }
+
+    pub fn display(&self) -> String { // "A [B] _" : la lettre en cours entre crochets, les suivantes pas encore choisies.
+        (0..INITIALS_LEN).map(|i| match i.cmp(&self.cursor) {
+            std::cmp::Ordering::Less => (ALPHABET[self.letters[i] as usize] as char).to_string(),
+            std::cmp::Ordering::Equal => format!("[{}]", ALPHABET[self.letters[i] as usize] as char),
+            std::cmp::Ordering::Greater => "_".to_string(),
+        }).collect::<Vec<_>>().join(" ")
+    }
+}
+
+pub fn table_text(scores: &HighScores, highlight: Option<usize>) -> String { // Le tableau, une partie par ligne.
+    if scores.entries.is_empty() { return "Pas encore de record".to_string(); }
+    scores.entries.iter().enumerate().map(|(i, e)| {
+        let mark = if highlight == Some(i) { "  <" } else { "" };
+        format!("{:>2}. {}  {:>7}  N{} V{}  {}{}", i + 1, e.initials, e.score, e.level, e.wave, e.date, mark)
+    }).collect::<Vec<_>>().join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_file(name: &str, content: Option<&str>) -> PathBuf { // Un fichier de scores jetable, propre à chaque test.
+        let dir = std::env::temp_dir().join(format!("xgalaga-scores-{}-{}", std::process::id(), name));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(FILE_NAME);
+        if let Some(content) = content { std::fs::write(&path, content).unwrap(); }
+        path
+    }
+
+    fn entry(initials: &str, score: u32) -> HighScore {
+        HighScore { initials: initials.into(), score, level: 1, wave: 2, date: "2026-01-01".into() }
+    }
+
+    #[test]
+    fn missing_file_is_an_empty_table() {
+        let (scores, problem) = HighScores::load(temp_file("missing", None));
+        assert!(scores.entries.is_empty());
+        assert_eq!(problem, None);
+    }
+
+    #[test]
+    fn corrupt_file_is_kept_aside_and_the_table_starts_empty() {
+        let path = temp_file("corrupt", Some("[ (initials: \"ABC\", score: "));
+        let (scores, problem) = HighScores::load(path.clone());
+        assert!(scores.entries.is_empty());
+        assert!(problem.expect("un message").contains("abîmé"));
+        assert!(!path.exists());
+        assert!(path.with_extension("ron.bak").exists());
+    }
+
+    #[test]
+    fn unreadable_file_is_never_overwritten() {
+        let path = temp_file("unreadable", None);
+        std::fs::create_dir_all(&path).unwrap(); // Un dossier à la place du fichier : la lecture échoue, mais pas parce qu'il manque.
+        let (mut scores, problem) = HighScores::load(path.clone());
+        assert!(problem.expect("un message").contains("illisible"));
+        scores.insert(entry("ABC", 100));
+        assert_eq!(scores.save(), Ok(()));
+        assert!(path.is_dir(), "le tableau reste en mémoire");
+    }
+
+    #[test]
+    fn saved_table_reads_back_sorted_and_trimmed() {
+        let path = temp_file("roundtrip", None);
+        let mut scores = HighScores { entries: Vec::new(), path: Some(path.clone()) };
+        for i in 0..12 { scores.insert(entry("abc", 100 * i)); }
+        scores.save().unwrap();
+        let (read, problem) = HighScores::load(path);
+        assert_eq!(problem, None);
+        assert_eq!(read.entries.len(), TABLE_SIZE);
+        assert_eq!(read.best(), 1100);
+        assert_eq!(read.entries[0].initials, "ABC", "les initiales sont remises en majuscules");
+        assert!(!read.qualifies(200) && read.qualifies(300));
+    }
+
+    #[test]
+    fn ties_keep_the_older_score_first() {
+        let mut scores = HighScores::default();
+        scores.insert(entry("OLD", 500));
+        assert_eq!(scores.insert(entry("NEW", 500)), 1);
+    }
+
+    #[test]
+    fn dates_are_gregorian() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(11_016), (2000, 2, 29));
+        assert_eq!(civil_from_days(20_744), (2026, 10, 18));
+    }
+}
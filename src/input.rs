@@ -0,0 +1,39 @@
+// ═══════════════════════════════════════════════════════════════════════════
+// 🕹️ LES COMMANDES DU JOUEUR : ce que la simulation a le droit de savoir
+// ═══════════════════════════════════════════════════════════════════════════
+//
+// La simulation avance par pas fixes, pas au rythme des images : un appui sur
+// Espace pendant une image sans pas de simulation serait perdu, et un appui
+// pendant une image avec deux pas tirerait deux fois. On lit donc le clavier
+// à chaque image dans `PlayerInput`, et la simulation ne lit que ça. C'est
+// aussi ce qu'on enregistre dans les replays (voir replay.rs).
+
+use bevy::prelude::*;
+
+use crate::AppState;
+
+#[derive(Resource, Default, Clone, Copy, Debug, PartialEq)]
+pub struct PlayerInput { // Les commandes du joueur pour le prochain pas de simulation.
+    pub left: bool, // Flèche gauche enfoncée.
+    pub right: bool, // Flèche droite enfoncée.
+    pub fire: bool, // Espace a été appuyé depuis le dernier pas.
+    pub pause: bool, // P a été appuyé depuis le dernier pas.
+}
+
+pub fn read_keyboard_system(kb: Res<ButtonInput<KeyCode>>, mut input: ResMut<PlayerInput>) { // À chaque image, on regarde le clavier.
+    input.left = kb.pressed(KeyCode::ArrowLeft);
+    input.right = kb.pressed(KeyCode::ArrowRight);
+    input.fire |= kb.just_pressed(KeyCode::Space); // On garde l'appui jusqu'au prochain pas.
+    input.pause |= kb.just_pressed(KeyCode::KeyP);
+}
+
+pub fn pause_system(input: Res<PlayerInput>, state: Res<State<AppState>>, mut next_state: ResMut<NextState<AppState>>) { // P met en pause, ou relance le jeu.
+    if !input.pause { return; }
+    match state.get() {
+        AppState::Playing => next_state.set(AppState::Paused),
+        AppState::Paused => next_state.set(AppState::Playing),
+        _ => {} // Pas de pause sur les autres écrans.
+    }
+}
+
+pub fn consume_presses_system(mut input: ResMut<PlayerInput>) { input.fire = false; input.pause = false; } // Le pas a vu les appuis : on les oublie.
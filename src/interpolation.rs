@@ -0,0 +1,104 @@
+// ═══════════════════════════════════════════════════════════════════════════
+// ⏱️ LE PAS FIXE : la simulation avance par petits pas toujours égaux
+// ═══════════════════════════════════════════════════════════════════════════
+//
+// Avant, tout bougeait avec le temps de l'image (`time.delta()`) : à 30 images
+// par seconde une balle faisait de grands sauts et pouvait traverser un alien
+// qu'elle aurait touché à 240. Maintenant, le jeu tourne dans `FixedUpdate`,
+// 60 fois par seconde quoi qu'il arrive, et la partie est la même sur tous
+// les ordinateurs.
+//
+// Pour que l'image reste fluide entre deux pas, on dessine chaque objet entre
+// sa position du pas d'avant et celle du pas actuel (l'interpolation). La
+// vraie position est notée à la fin de chaque pas et remise avant le suivant :
+// la simulation ne voit jamais la position "dessinée", même quand une image
+// passe sans aucun pas.
+
+use bevy::prelude::*;
+
+pub const SIMULATION_HZ: f64 = 60.0; // Le nombre de pas de simulation par seconde.
+const TELEPORT_DISTANCE: f32 = 200.0; // Au-delà de ce saut en un pas, c'est une téléportation : on ne glisse pas.
+
+#[derive(Component, Default)]
+pub struct Interpolated { // Les deux dernières positions de simulation d'un objet.
+    previous: Vec3,
+    current: Vec3,
+    ready: bool, // Faux tant que l'objet vient d'apparaître.
+}
+
+impl Interpolated {
+    #[cfg(test)]
+    pub fn simulated(&self) -> Option<Vec3> { self.ready.then_some(self.current) } // La vraie position au dernier pas (None avant le premier).
+}
+
+pub fn restore_simulation_system(mut query: Query<(&mut Transform, &mut Interpolated)>) { // Avant un pas : on remet la vraie position.
+    for (mut trans, mut interp) in query.iter_mut() {
+        if !interp.ready { continue; }
+        trans.translation = interp.current;
+        interp.previous = interp.current;
+    }
+}
+
+pub fn record_simulation_system(mut query: Query<(&Transform, &mut Interpolated)>) { // À la fin d'un pas : on note la vraie position.
+    for (trans, mut interp) in query.iter_mut() {
+        interp.current = trans.translation;
+        if !interp.ready || interp.previous.distance(interp.current) > TELEPORT_DISTANCE {
+            interp.previous = interp.current;
+            interp.ready = true;
+        }
+    }
+}
+
+pub fn interpolate_system(fixed: Res<Time<Fixed>>, mut query: Query<(&mut Transform, &Interpolated)>) { // Après les pas : on dessine entre les deux positions.
+    let alpha = fixed.overstep_fraction();
+    for (mut trans, interp) in query.iter_mut() {
+        if !interp.ready { continue; } // Pas encore passé par un pas : on le laisse où il est.
+        trans.translation = interp.previous.lerp(interp.current, alpha);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::time::TimeUpdateStrategy;
+
+    use super::*;
+
+    const TICK: Duration = Duration::from_millis(20); // Un pas de 20 ms : des durées d'image exactes.
+
+    #[derive(Resource, Default)]
+    struct Ticks(u32);
+
+    fn walk_system(mut ticks: ResMut<Ticks>, mut query: Query<&mut Transform, With<Interpolated>>) { // Un pas = un pixel vers la droite.
+        ticks.0 += 1;
+        for mut trans in query.iter_mut() { trans.translation.x += 1.0; }
+    }
+
+    fn walk(frame: Duration, frames: u32) -> (u32, Vec3, Vec3) { // Les pas faits, la vraie position et celle dessinée.
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .insert_resource(Time::<Fixed>::from_duration(TICK))
+            .insert_resource(TimeUpdateStrategy::ManualDuration(frame))
+            .init_resource::<Ticks>()
+            .add_systems(FixedFirst, restore_simulation_system)
+            .add_systems(FixedUpdate, walk_system)
+            .add_systems(FixedLast, record_simulation_system)
+            .add_systems(RunFixedMainLoop, interpolate_system.in_set(RunFixedMainLoopSystems::AfterFixedMainLoop));
+        let walker = app.world_mut().spawn((Transform::default(), Interpolated::default())).id();
+        for _ in 0..frames { app.update(); }
+        let world = app.world();
+        let interp = world.get::<Interpolated>(walker).unwrap();
+        (world.resource::<Ticks>().0, interp.current, world.get::<Transform>(walker).unwrap().translation)
+    }
+
+    #[test]
+    fn the_simulation_is_the_same_at_any_frame_rate() { // 1, 2 ou 4 pas par image, ou une image sur quatre sans pas.
+        for (frame, frames) in [(TICK, 40), (TICK * 2, 20), (TICK * 4, 10), (TICK / 4, 160)] {
+            let (ticks, simulated, drawn) = walk(frame, frames);
+            assert!(ticks >= 36, "{:?} par image : seulement {} pas", frame, ticks);
+            assert_eq!(simulated.x, ticks as f32, "{:?} par image : un pas a été perdu", frame);
+            assert!(drawn.x <= simulated.x && drawn.x >= simulated.x - 1.0, "{:?} par image : dessiné en {}", frame, drawn.x);
+        }
+    }
+}
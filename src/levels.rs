@@ -0,0 +1,293 @@
+// ═══════════════════════════════════════════════════════════════════════════
+// 📜 LES NIVEAUX EN FICHIERS : les vagues sont décrites dans assets/levels
+// ═══════════════════════════════════════════════════════════════════════════
+//
+// Avant, les vagues étaient écrites en dur dans `wave_system`. Maintenant, un
+// fichier `campaign.campaign.ron` donne la liste des niveaux, et chaque niveau
+// (`level_XX.level.ron`) décrit ses vagues : quels aliens, combien, d'où ils
+// arrivent, leur trajectoire d'entrée, leur rythme de tir et les bonus qu'ils
+// lâchent. Un designer peut fabriquer des niveaux sans recompiler le jeu.
+//
+// Si un fichier est faux, l'erreur donne le fichier et la ligne fautive,
+// par exemple : "levels/level_02.level.ron:14:9: vague 3 : `count` doit être > 0".
+
+use std::fmt;
+
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, LoadContext};
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::formation;
+use crate::starfield::StarfieldDef;
+use crate::{BonusType, EnemyType, SpawnDirection};
+
+pub const CAMPAIGN_PATH: &str = "levels/campaign.campaign.ron"; // Le fichier qui liste les niveaux.
+
+#[derive(Asset, TypePath, Debug)]
+pub struct CampaignAsset { pub levels: Vec<Handle<LevelAsset>> } // La liste des niveaux, dans l'ordre.
+
+#[derive(Asset, TypePath, Debug, Deserialize)]
+pub struct LevelAsset { // Un niveau : un nom, les bonus par défaut et une suite de vagues.
+    pub name: String,
+    #[serde(default)]
+    pub drops: DropTable, // Les bonus lâchés par les aliens, sauf si la vague a sa propre table.
+    #[serde(default)]
+    pub starfield: StarfieldDef, // Le ciel étoilé derrière le niveau (sinon, celui par défaut).
+    pub waves: Vec<WaveDef>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WaveDef { // Une vague d'aliens.
+    pub direction: SpawnDirection, // D'où ils arrivent.
+    #[serde(default)]
+    pub entry: EntryStyle, // La forme de leur trajectoire jusqu'à leur place.
+    #[serde(default)]
+    pub spawn_interval: Option<f32>, // Le temps entre deux aliens qui arrivent (sinon, celui des réglages).
+    pub enemies: Vec<EnemyGroup>, // Les aliens, dans l'ordre d'arrivée.
+    #[serde(default)]
+    pub drops: Option<DropTable>, // Les bonus qu'ils lâchent en mourant (sinon, ceux du niveau).
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EnemyGroup { // Un paquet d'aliens identiques.
+    pub kind: EnemyType,
+    pub count: usize,
+    pub fire_interval: f32, // Le temps entre deux tirs de chaque alien.
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq)]
+pub enum EntryStyle { // Les trajectoires d'entrée possibles.
+    #[default] Swoop, // Il passe sous la grille avant de remonter à sa place.
+    Plunge, // Il plonge très bas avant de remonter.
+    Direct, // Il va tout droit à sa place.
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct DropTable { // La chance de lâcher un bonus, et lequel.
+    pub chance: f32, // Entre 0 (jamais) et 1 (toujours).
+    pub table: Vec<DropEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DropEntry { pub weight: u32, pub bonus: BonusType } // Plus le poids est gros, plus le bonus tombe souvent.
+
+impl WaveDef {
+    pub fn size(&self) -> usize { self.enemies.iter().map(|g| g.count).sum() } // Le nombre total d'aliens de la vague.
+
+    pub fn group_at(&self, index: usize) -> Option<&EnemyGroup> { // Le paquet auquel appartient le n-ième alien.
+        let mut index = index;
+        for group in &self.enemies {
+            if index < group.count { return Some(group); }
+            index -= group.count;
+        }
+        None
+    }
+}
+
+impl DropTable {
+    pub fn pick(&self, chance_roll: f32, weight_roll: u32) -> Option<BonusType> { // On tire au sort un bonus (ou rien).
+        let total: u32 = self.table.iter().map(|e| e.weight).sum();
+        if chance_roll >= self.chance || total == 0 { return None; }
+        let mut roll = weight_roll % total;
+        for entry in &self.table {
+            if roll < entry.weight { return Some(entry.bonus.clone()); }
+            roll -= entry.weight;
+        }
+        None
+    }
+}
+
+impl EntryStyle {
+    pub fn control_point(self, direction: SpawnDirection, start: Vec2) -> Vec2 { // Le point de contrôle de la courbe d'entrée.
+        match (self, direction) {
+            (EntryStyle::Swoop, SpawnDirection::Top) => Vec2::new(-start.x * 0.5, -220.0), // Du haut : il plonge puis remonte en croisant.
+            (EntryStyle::Swoop, _) => Vec2::new(-start.x * 0.2, -180.0), // Des côtés : il passe sous la grille avant de remonter.
+            (EntryStyle::Plunge, _) => Vec2::new(-start.x * 0.3, -420.0),
+            (EntryStyle::Direct, _) => start * 0.5 + Vec2::new(0.0, 100.0),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum LevelLoadError { // Tout ce qui peut mal se passer en lisant un fichier de niveau.
+    Io(std::io::Error),
+    Parse { path: String, line: usize, col: usize, message: String }, // Le fichier n'est pas du RON correct.
+    Invalid { path: String, line: usize, message: String }, // Le RON est correct, mais les valeurs n'ont pas de sens.
+}
+
+impl fmt::Display for LevelLoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LevelLoadError::Io(err) => write!(f, "impossible de lire le fichier : {}", err),
+            LevelLoadError::Parse { path, line, col, message } => write!(f, "{}:{}:{}: {}", path, line, col, message),
+            LevelLoadError::Invalid { path, line, message } => write!(f, "{}:{}: {}", path, line, message),
+        }
+    }
+}
+
+impl std::error::Error for LevelLoadError {}
+
+impl From<std::io::Error> for LevelLoadError {
+    fn from(err: std::io::Error) -> Self { LevelLoadError::Io(err) }
+}
+
+pub(crate) fn parse<T: for<'de> Deserialize<'de>>(text: &str, path: &str) -> Result<T, LevelLoadError> { // On lit le RON en gardant la position des erreurs.
+    ron::de::from_str(text).map_err(|err| LevelLoadError::Parse {
+        path: path.to_string(), line: err.position.line, col: err.position.col, message: err.code.to_string(),
+    })
+}
+
+pub(crate) fn line_of(text: &str, offset: usize) -> usize { text[..offset].matches('\n').count() + 1 } // Le numéro de ligne d'une position dans le texte.
+
+fn list_item_line(text: &str, key: &str, index: usize) -> usize { // La ligne où commence le n-ième élément de la liste `key: [...]`.
+    let Some(key_at) = text.find(&format!("{}:", key)) else { return 1 };
+    let Some(open) = text[key_at..].find('[').map(|i| key_at + i) else { return line_of(text, key_at) };
+    let (mut depth, mut seen, mut in_string, mut in_comment) = (0, 0, false, false);
+    let mut chars = text[open..].char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\n' => in_comment = false,
+            _ if in_comment => {}
+            '"' => in_string = !in_string,
+            _ if in_string => {}
+            '/' if matches!(chars.peek(), Some((_, '/'))) => in_comment = true, // On saute les commentaires.
+            '[' | '(' | '{' => {
+                if depth == 1 && c == '(' { // Un nouvel élément de la liste commence ici.
+                    if seen == index { return line_of(text, open + i); }
+                    seen += 1;
+                }
+                depth += 1;
+            }
+            ']' | ')' | '}' => { depth -= 1; if depth == 0 { break; } }
+            _ => {}
+        }
+    }
+    line_of(text, open)
+}
+
+fn validate_drops(drops: &DropTable, context: &str) -> Result<(), String> { // Une table de bonus doit avoir du sens.
+    if !(0.0..=1.0).contains(&drops.chance) { return Err(format!("{} : `drops.chance` doit être entre 0 et 1", context)); }
+    if drops.chance > 0.0 && drops.table.iter().all(|e| e.weight == 0) { return Err(format!("{} : `drops.table` n'a aucun bonus avec un poids > 0", context)); }
+    Ok(())
+}
+
+fn validate(level: &LevelAsset, text: &str, path: &str) -> Result<(), LevelLoadError> { // On vérifie que le niveau est jouable.
+    let invalid = |line: usize, message: String| LevelLoadError::Invalid { path: path.to_string(), line, message };
+    let waves_at = text.find("waves:").unwrap_or(text.len());
+    let drops_line = text[..waves_at].find("drops:").map(|i| line_of(text, i)).unwrap_or(1); // La table du niveau est écrite avant les vagues.
+    validate_drops(&level.drops, "niveau").map_err(|message| invalid(drops_line, message))?;
+    let starfield_line = text.find("starfield:").map(|i| line_of(text, i)).unwrap_or(1);
+    level.starfield.check().map_err(|message| invalid(starfield_line, message))?;
+    if level.waves.is_empty() { return Err(invalid(list_item_line(text, "waves", 0), "le niveau doit contenir au moins une vague".into())); }
+    for (i, wave) in level.waves.iter().enumerate() {
+        let line = list_item_line(text, "waves", i);
+        let wave_no = i + 1;
+        if wave.enemies.is_empty() { return Err(invalid(line, format!("vague {} : la liste `enemies` est vide", wave_no))); }
+        if wave.spawn_interval.is_some_and(|i| i <= 0.0) { return Err(invalid(line, format!("vague {} : `spawn_interval` doit être > 0", wave_no))); }
+        for group in &wave.enemies {
+            if group.count == 0 { return Err(invalid(line, format!("vague {} : `count` doit être > 0", wave_no))); }
+            if group.fire_interval <= 0.0 { return Err(invalid(line, format!("vague {} : `fire_interval` doit être > 0", wave_no))); }
+        }
+        let bosses: usize = wave.enemies.iter().filter(|g| g.kind == EnemyType::Boss).map(|g| g.count).sum();
+        let (boss_slots, soldier_slots) = formation::capacity();
+        if bosses > boss_slots || wave.size() - bosses > soldier_slots {
+            return Err(invalid(line, format!("vague {} : trop d'aliens pour la grille ({} chefs et {} soldats au maximum)", wave_no, boss_slots, soldier_slots)));
+        }
+        if let Some(drops) = &wave.drops { validate_drops(drops, &format!("vague {}", wave_no)).map_err(|message| invalid(line, message))?; }
+    }
+    Ok(())
+}
+
+#[derive(Default, TypePath)]
+pub struct LevelLoader; // Le lecteur des fichiers `.level.ron`.
+
+impl AssetLoader for LevelLoader {
+    type Asset = LevelAsset;
+    type Settings = ();
+    type Error = LevelLoadError;
+
+    async fn load(&self, reader: &mut dyn Reader, _settings: &(), load_context: &mut LoadContext<'_>) -> Result<LevelAsset, LevelLoadError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let path = load_context.path().display().to_string();
+        let text = String::from_utf8_lossy(&bytes);
+        let level: LevelAsset = parse(&text, &path)?;
+        validate(&level, &text, &path)?;
+        Ok(level)
+    }
+
+    fn extensions(&self) -> &[&str] { &["level.ron"] }
+}
+
+#[derive(Deserialize)]
+struct CampaignFile { levels: Vec<String> } // Le fichier de campagne tel qu'il est écrit.
+
+#[derive(Default, TypePath)]
+pub struct CampaignLoader; // Le lecteur du fichier `.campaign.ron`.
+
+impl AssetLoader for CampaignLoader {
+    type Asset = CampaignAsset;
+    type Settings = ();
+    type Error = LevelLoadError;
+
+    async fn load(&self, reader: &mut dyn Reader, _settings: &(), load_context: &mut LoadContext<'_>) -> Result<CampaignAsset, LevelLoadError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let path = load_context.path().display().to_string();
+        let text = String::from_utf8_lossy(&bytes);
+        let file: CampaignFile = parse(&text, &path)?;
+        if file.levels.is_empty() {
+            return Err(LevelLoadError::Invalid { path, line: list_item_line(&text, "levels", 0), message: "la campagne doit contenir au moins un niveau".into() });
+        }
+        Ok(CampaignAsset { levels: file.levels.into_iter().map(|level| load_context.load(level)).collect() }) // Chaque niveau est chargé à son tour.
+    }
+
+    fn extensions(&self) -> &[&str] { &["campaign.ron"] }
+}
+
+#[derive(Resource)]
+pub struct Campaign(pub Handle<CampaignAsset>); // La campagne en cours de chargement (ou chargée).
+
+pub fn load_campaign(mut commands: Commands, asset_server: Res<AssetServer>) { // On lance le chargement au démarrage.
+    commands.insert_resource(Campaign(asset_server.load(CAMPAIGN_PATH)));
+}
+
+#[derive(SystemParam)]
+pub struct Levels<'w> { // Un raccourci pour lire les niveaux depuis un système.
+    campaign: Option<Res<'w, Campaign>>,
+    campaigns: Res<'w, Assets<CampaignAsset>>,
+    levels: Res<'w, Assets<LevelAsset>>,
+}
+
+impl Levels<'_> {
+    fn campaign(&self) -> Option<&CampaignAsset> { self.campaigns.get(&self.campaign.as_ref()?.0) }
+
+    pub fn level_count(&self) -> Option<usize> { self.campaign().map(|c| c.levels.len()) } // Combien de niveaux (None tant que ce n'est pas chargé).
+
+    pub fn level(&self, level: u32) -> Option<&LevelAsset> { // Le niveau demandé (ils commencent à 1).
+        let handle = self.campaign()?.levels.get(level.checked_sub(1)? as usize)?;
+        self.levels.get(handle)
+    }
+
+    pub fn wave(&self, level: u32, wave: u32) -> Option<&WaveDef> { self.level(level)?.waves.get(wave.checked_sub(1)? as usize) } // La vague demandée (elles commencent à 1).
+
+    pub fn all_loaded(&self) -> bool { // La campagne et tous ses niveaux sont-ils chargés ?
+        self.campaign().is_some_and(|c| c.levels.iter().all(|handle| self.levels.contains(handle)))
+    }
+
+    pub fn clamp_progress(&self, level: u32, wave: u32) -> Option<(u32, u32)> { // Où reprendre si les fichiers ont raccourci (None : la vague existe toujours).
+        let count = self.level_count()? as u32;
+        let new_level = level.clamp(1, count.max(1)); // Trop loin dans la campagne : on reprend au début du dernier niveau.
+        let waves = self.level(new_level)?.waves.len() as u32;
+        let new_wave = if new_level == level { wave.clamp(1, waves.max(1)) } else { 1 };
+        ((new_level, new_wave) != (level, wave)).then_some((new_level, new_wave))
+    }
+
+    pub fn drops(&self, level: u32, wave: u32) -> Option<&DropTable> { // La table de bonus de la vague, ou celle du niveau.
+        self.wave(level, wave)?.drops.as_ref().or(self.level(level).map(|l| &l.drops))
+    }
+}
+
+pub fn levels_ready(levels: Levels) -> bool { levels.all_loaded() } // La simulation attend les niveaux, pour que chaque partie commence au même pas.
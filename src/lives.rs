@@ -0,0 +1,238 @@
+// ═══════════════════════════════════════════════════════════════════════════
+// 🚀 LES VIES : le vaisseau explose, la formation se regroupe, un nouveau arrive
+// ═══════════════════════════════════════════════════════════════════════════
+//
+// Avant, une vie perdue n'était qu'un compteur qui baissait : le vaisseau
+// continuait de voler comme si de rien n'était. Maintenant, comme dans Galaga :
+//
+//   1. le vaisseau touché explose et disparaît ;
+//   2. les aliens arrêtent de tirer et ceux qui plongeaient rentrent dans la grille ;
+//   3. quand tout le monde est rangé, un vaisseau de réserve arrive en bas et
+//      clignote quelques secondes : pendant ce temps, rien ne peut le toucher ;
+//   4. s'il n'y a plus de vaisseau en réserve, c'est "GAME OVER".
+//
+// Les vaisseaux de réserve sont dessinés en petit dans le bandeau du haut. Un
+// vaisseau ne peut être touché qu'une fois par pas, même si trois balles
+// arrivent en même temps.
+//
+// Comme à l'arcade, certains scores donnent un vaisseau de plus (par exemple à
+// 20 000, à 70 000, puis tous les 70 000). Sur la borne, l'exploitant choisissait
+// la table avec des DIP switches ; ici, c'est `extra_ships` dans le fichier de
+// réglages. Une petite fanfare joue et la réserve clignote quand on en gagne un.
+
+use bevy::audio::Pitch;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::animation::{self, Animation};
+use crate::collider::Collider;
+use crate::formation::Flight;
+use crate::messages::PlayerHit;
+use crate::sounds;
+use crate::sprites::{Skin, SpriteId, Sprites};
+use crate::tractor::DualFighter;
+use crate::tuning::Tuning;
+use crate::{spawn_player, AppState, Enemy, Explosion, GameState, InGame, PLAYER_LIVES};
+
+const SHIP_EXPLOSION_DURATION: f32 = 1.0; // L'explosion du vaisseau dure plus longtemps que celle d'un alien.
+const SHIP_EXPLOSION_SIZE: f32 = 90.0;
+const RESPAWN_DELAY: f32 = 2.0; // Le temps minimum entre l'explosion et le vaisseau suivant (ou "GAME OVER").
+const INVULNERABLE_DURATION: f32 = 2.5; // Le nouveau vaisseau ne craint rien pendant ce temps...
+const BLINK_PERIOD: f32 = 0.1; // ... et il clignote pour qu'on le voie.
+const ICON_SIZE: Vec2 = Vec2::new(24.0, 12.0); // La taille d'un vaisseau de réserve dans le bandeau.
+const FANFARE_NOTES: [f32; 4] = [523.25, 659.25, 783.99, 1046.5]; // Do, mi, sol, do : la fanfare du vaisseau bonus.
+const FANFARE_NOTE: f32 = 0.12; // La durée d'une note.
+const FANFARE_FLASH: f32 = 2.0; // Le temps pendant lequel la réserve clignote.
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum ExtraShips { // Les tables de vaisseaux bonus de la borne.
+    At20kThen60k, // 20 000, 60 000, puis tous les 60 000.
+    #[default]
+    At20kThen70k, // 20 000, 70 000, puis tous les 70 000.
+    At20kThen80k, // 20 000, 80 000, puis tous les 80 000.
+    At30kThen100k, // 30 000, 100 000, puis tous les 100 000.
+    Only20kAnd60k, // 20 000 et 60 000, c'est tout.
+    Only20kAnd70k, // 20 000 et 70 000, c'est tout.
+    Only30kAnd80k, // 30 000 et 80 000, c'est tout.
+    Never, // Pas de vaisseau bonus.
+}
+
+impl ExtraShips {
+    fn table(self) -> (&'static [u32], Option<u32>) { // Les premiers paliers, puis l'écart entre les suivants.
+        match self {
+            Self::At20kThen60k => (&[20_000, 60_000], Some(60_000)),
+            Self::At20kThen70k => (&[20_000, 70_000], Some(70_000)),
+            Self::At20kThen80k => (&[20_000, 80_000], Some(80_000)),
+            Self::At30kThen100k => (&[30_000, 100_000], Some(100_000)),
+            Self::Only20kAnd60k => (&[20_000, 60_000], None),
+            Self::Only20kAnd70k => (&[20_000, 70_000], None),
+            Self::Only30kAnd80k => (&[30_000, 80_000], None),
+            Self::Never => (&[], None),
+        }
+    }
+
+    pub fn threshold(self, n: u32) -> Option<u32> { // Le score qui donne le n-ième vaisseau bonus (en comptant depuis 0).
+        let (firsts, every) = self.table();
+        if let Some(&score) = firsts.get(n as usize) { return Some(score); }
+        let beyond = n + 1 - firsts.len() as u32; // Combien d'écarts après le dernier palier fixe.
+        every?.checked_mul(beyond).and_then(|gap| firsts.last()?.checked_add(gap))
+    }
+}
+
+#[derive(Resource)]
+pub struct Lives { pub ships: i32, respawn: Option<Timer>, awarded: u32, fanfare: Option<Timer> } // Les vaisseaux qui restent (celui qui vole compris), le chrono du suivant quand il est détruit, les vaisseaux bonus déjà gagnés et la fanfare en cours.
+
+impl Default for Lives {
+    fn default() -> Self { Self { ships: PLAYER_LIVES, respawn: None, awarded: 0, fanfare: None } }
+}
+
+impl Lives {
+    pub fn ship_down(&self) -> bool { self.respawn.is_some() } // Vrai entre l'explosion et l'arrivée du vaisseau suivant.
+
+    pub fn lose_ship(&mut self) { // Un vaisseau de moins ; le suivant arrivera après la pause.
+        self.ships -= 1;
+        self.respawn = Some(Timer::from_seconds(RESPAWN_DELAY, TimerMode::Once));
+    }
+
+    pub fn spares(&self) -> usize { (self.ships - 1).max(0) as usize } // Les vaisseaux en réserve, sans celui qui vole.
+}
+
+#[derive(Component)]
+pub struct Invulnerable { timer: Timer } // Le vaisseau qui vient d'arriver ne peut pas être touché.
+
+#[derive(Component)] pub struct SpareShips; // La rangée de petits vaisseaux dans le bandeau.
+
+pub fn player_hit_system( // Le vaisseau est touché : le double chasseur perd son ailier, sinon c'est l'explosion.
+    mut commands: Commands,
+    sprites: Res<Sprites>,
+    tuning: Res<Tuning>,
+    mut lives: ResMut<Lives>,
+    mut player_hit: MessageReader<PlayerHit>,
+) {
+    for hit in player_hit.read() {
+        match hit.wingman {
+            Some(wingman) => { // On perd seulement le vaisseau de droite.
+                if let Ok(mut cmd) = commands.get_entity(wingman) { cmd.despawn(); }
+                if let Ok(mut cmd) = commands.get_entity(hit.ship) { cmd.remove::<DualFighter>().insert(Collider::player(&tuning, false)); }
+                crate::spawn_explosion(&mut commands, &sprites, hit.position + Vec3::new(tuning.dual_offset(), 0.0, 0.0));
+            }
+            None => {
+                if let Ok(mut cmd) = commands.get_entity(hit.ship) { cmd.despawn(); }
+                commands.spawn((
+                    Explosion { timer: Timer::from_seconds(SHIP_EXPLOSION_DURATION, TimerMode::Once) },
+                    Skin(SpriteId::Explosion),
+                    Animation::play(animation::BURST),
+                    Sprite { custom_size: Some(Vec2::splat(SHIP_EXPLOSION_SIZE)), color: Color::srgb(1.0, 0.8, 0.6), ..sprites.sprite(SpriteId::Explosion) },
+                    Transform::from_translation(hit.position),
+                ));
+                lives.lose_ship();
+            }
+        }
+    }
+}
+
+pub fn respawn_system( // Après l'explosion : "GAME OVER", ou un nouveau vaisseau dès que la formation est regroupée.
+    mut commands: Commands,
+    time: Res<Time>,
+    sprites: Res<Sprites>,
+    tuning: Res<Tuning>,
+    mut lives: ResMut<Lives>,
+    mut next_state: ResMut<NextState<AppState>>,
+    enemy_q: Query<&Flight, With<Enemy>>,
+) {
+    let Some(timer) = lives.respawn.as_mut() else { return };
+    timer.tick(time.delta());
+    if !timer.is_finished() { return; }
+    if lives.ships <= 0 { next_state.set(AppState::GameOver); return; }
+    if enemy_q.iter().any(|flight| matches!(flight, Flight::Diving)) { return; } // On attend que les plongeurs soient rentrés.
+    lives.respawn = None;
+    let player = spawn_player(&mut commands, &sprites, &tuning);
+    commands.entity(player).insert(Invulnerable { timer: Timer::from_seconds(INVULNERABLE_DURATION, TimerMode::Once) });
+}
+
+pub fn invulnerability_system(mut commands: Commands, time: Res<Time>, mut query: Query<(Entity, &mut Invulnerable, &mut Visibility)>) { // Le nouveau vaisseau clignote, puis redevient normal.
+    for (entity, mut shield, mut visibility) in query.iter_mut() {
+        shield.timer.tick(time.delta());
+        if shield.timer.is_finished() {
+            commands.entity(entity).remove::<Invulnerable>();
+            *visibility = Visibility::Inherited;
+            continue;
+        }
+        let blink_on = ((shield.timer.elapsed_secs() / BLINK_PERIOD) as u32).is_multiple_of(2);
+        *visibility = if blink_on { Visibility::Inherited } else { Visibility::Hidden };
+    }
+}
+
+pub fn extra_ship_system(mut lives: ResMut<Lives>, tuning: Res<Tuning>, state: Res<GameState>) { // Un palier de score franchi : un vaisseau de plus.
+    while let Some(threshold) = tuning.extra_ships.threshold(lives.awarded) {
+        if state.score < threshold { break; }
+        lives.awarded += 1;
+        lives.ships += 1;
+        lives.fanfare = Some(Timer::from_seconds(FANFARE_FLASH, TimerMode::Once));
+    }
+}
+
+pub fn fanfare_system( // La fanfare joue ses notes une à une et la réserve clignote.
+    mut commands: Commands,
+    time: Res<Time>,
+    mut lives: ResMut<Lives>,
+    pitches: Option<ResMut<Assets<Pitch>>>, // Pas de son sans fenêtre.
+    mut row_q: Query<&mut Visibility, With<SpareShips>>,
+) {
+    let Some(timer) = lives.fanfare.as_mut() else { return };
+    let before = timer.elapsed_secs();
+    timer.tick(time.delta());
+    let (now, finished) = (timer.elapsed_secs(), timer.is_finished());
+    if let Some(mut pitches) = pitches {
+        for (i, &frequency) in FANFARE_NOTES.iter().enumerate() {
+            let start = i as f32 * FANFARE_NOTE;
+            if before <= start && start < now { // Chaque note part quand son tour arrive.
+                sounds::beep(&mut commands, &mut pitches, frequency, FANFARE_NOTE);
+            }
+        }
+    }
+    let blink_on = ((now / (BLINK_PERIOD * 2.0)) as u32).is_multiple_of(2);
+    for mut visibility in row_q.iter_mut() {
+        *visibility = if finished || blink_on { Visibility::Inherited } else { Visibility::Hidden };
+    }
+    if finished { lives.fanfare = None; }
+}
+
+pub fn spawn_spare_ships(parent: &mut ChildSpawnerCommands) { // La place des vaisseaux de réserve dans le bandeau.
+    parent.spawn((SpareShips, Node { column_gap: Val::Px(6.0), align_items: AlignItems::Center, ..default() }));
+}
+
+pub fn spare_ships_system( // On redessine les vaisseaux de réserve quand leur nombre change.
+    mut commands: Commands,
+    sprites: Res<Sprites>,
+    lives: Res<Lives>,
+    in_game: Option<Res<State<InGame>>>,
+    row_q: Query<(Entity, Option<&Children>), With<SpareShips>>,
+) {
+    let Ok((row, children)) = row_q.single() else { return };
+    let wanted = if in_game.is_some() { lives.spares() } else { 0 }; // Pas de réserve affichée hors d'une partie.
+    if children.map_or(0, |c| c.len()) == wanted { return; }
+    commands.entity(row).despawn_children().with_children(|parent| {
+        for _ in 0..wanted {
+            parent.spawn((Skin(SpriteId::Player), sprites.icon(SpriteId::Player), Node { width: Val::Px(ICON_SIZE.x), height: Val::Px(ICON_SIZE.y), ..default() }));
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn twenty_then_seventy_then_every_seventy() {
+        let table = ExtraShips::At20kThen70k;
+        assert_eq!((0..5).map(|n| table.threshold(n)).collect::<Vec<_>>(), [Some(20_000), Some(70_000), Some(140_000), Some(210_000), Some(280_000)]);
+    }
+
+    #[test]
+    fn limited_tables_stop() {
+        assert_eq!(ExtraShips::Only30kAnd80k.threshold(1), Some(80_000));
+        assert_eq!(ExtraShips::Only30kAnd80k.threshold(2), None);
+        assert_eq!(ExtraShips::Never.threshold(0), None);
+    }
+}
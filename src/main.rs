@@ -6,7 +6,7 @@
 //
 // Message correct
 // le vaisseau ok
-// Utilisation des touches "Q" "X" pour sortir, "P" pour pause, "R" pour recommencer le jeu
+// Utilisation des touches "Q" "X" pour sortir, "P" pour pause, "R" pour revenir à l'écran titre
 // Aliens du haut de couelur gris, de gauvhe de couelur gauche, de droite de couelur verte
 // Forcage des 3 premieres vagues, 1er Level: 
 //          La 1ere vague vient de la la gauche, 
@@ -21,38 +21,99 @@
 // 🛸 XGALAGA RUST - VERSION EXPLIQUÉE POUR LES FUTURS GÉNIES
 // ═══════════════════════════════════════════════════════════════════════════
 
+mod animation; // Les planches d'images qui défilent.
+mod aseprite; // Les planches exportées par Aseprite.
+mod challenge; // Les stages bonus où les aliens ne tirent pas.
+mod cli; // Les options de la ligne de commande.
+mod collider; // Les zones de choc et qui peut toucher qui.
+mod damage; // Les tirs que les aliens encaissent avant de mourir.
+mod dive; // Les attaques en piqué des aliens.
+mod formation; // La grille où les aliens viennent se ranger.
+mod headless; // Le jeu sans fenêtre, pour les simulations et les tests.
+mod highscores; // Les meilleurs scores, gardés dans un fichier.
+mod input; // Les commandes du joueur lues à chaque image.
+mod interpolation; // La simulation à pas fixe, dessinée en douceur.
+mod levels; // Les niveaux et les vagues décrits dans des fichiers.
+mod lives; // Les vaisseaux de réserve, l'explosion et le retour du vaisseau.
+mod messages; // Les messages "alien abattu", "vaisseau touché"... et ceux qui les écoutent.
+mod paths; // Les courbes que les aliens peuvent suivre.
+mod playfield; // La taille de l'aire de jeu, avec ou sans fenêtre.
+mod replay; // L'enregistrement et la relecture des parties.
+mod rng; // Le hasard reproductible à partir d'une graine.
+#[cfg(test)]
+mod scenario; // Les tests de règles du jeu, en situation.
+mod screens; // L'écran titre, la fin de partie et le tableau des scores.
+mod sounds; // Les petits bips du jeu.
+mod sprites; // Les images du jeu, dessinées par lui quand les fichiers manquent.
+mod starfield; // Le ciel étoilé qui défile derrière la partie.
+mod stats; // Les statistiques de la partie et les succès.
+mod tractor; // Le rayon tracteur du chef et le double chasseur.
+mod tuning; // Les vitesses, tailles et chronos lus dans un fichier.
+
 use bevy::prelude::*; // On importe les outils de Bevy pour fabriquer le jeu.
-use bevy::window::PrimaryWindow; // On importe l'outil pour regarder la fenêtre du jeu.
 use bevy::app::AppExit; // On importe l'outil pour pouvoir fermer le jeu proprement.
+use animation::Animation; // Les aliens qui battent des ailes, les explosions...
+use aseprite::{AsepriteLoader, AsepriteSheet}; // Les planches exportées par Aseprite.
+use challenge::{ChallengeFlyer, StageKind}; // Les stages bonus.
+use cli::CliOptions; // Les options du lancement.
+use collider::{Collider, Contacts, Layer}; // Les zones de choc.
+use damage::HitOutcome; // Ce qu'un tir fait à un alien.
+use dive::DiveScheduler; // Le chrono des plongeons.
+use formation::{Flight, Formation}; // La grille des aliens.
+use highscores::{HighScores, InitialsEntry}; // Le tableau des records.
+use input::PlayerInput; // Les commandes du joueur.
+use interpolation::Interpolated; // Les objets dessinés entre deux pas de simulation.
+use levels::{CampaignAsset, CampaignLoader, LevelAsset, LevelLoader, Levels}; // Les fichiers de niveaux.
+use lives::{Invulnerable, Lives}; // Les vies du joueur.
+use messages::{EnemyDamaged, EnemyKilled, PlayerHit, PowerUpCollected, WaveCleared}; // Ce qui se passe pendant la partie.
+use playfield::Playfield; // La taille de l'écran.
+use rand::Rng; // Pour tirer des nombres au sort dans les flux de hasard.
+use replay::{Recording, Replay}; // Les parties enregistrées.
+use rng::GameRng; // Le hasard de la partie.
+use screens::{FirstScreen, ScreenTimer}; // L'écran après le chargement, et le chrono des écrans de fin.
+use serde::Deserialize; // Pour lire les types du jeu dans les fichiers de niveaux.
+use sprites::{Skin, SpriteId, SpritePack, SpritePackLoader, Sprites}; // Les images, choisies dans le pack d'images.
+use starfield::Sky; // Le ciel étoilé derrière la partie.
+use stats::{Achievements, GameStats}; // Ce que le joueur a fait.
+use tractor::{BeingCaptured, CapturedFighter, DualFighter}; // Capture et sauvetage du vaisseau.
+use tuning::{StatusMessage, StatusText, Tuning, TuningLoader}; // Les réglages qu'on modifie en jouant.
+
+// Les vitesses, les tailles et les chronos des vagues sont dans assets/tuning.tuning.ron (voir tuning.rs).
+const PLAYER_LIVES: i32 = 3; // Le nombre de vaisseaux (3 pour commencer, celui qui vole compris).
+const DEFAULT_HEADLESS_FRAMES: u64 = 36_000; // Sans fenêtre, on simule 10 minutes de jeu si on ne dit rien.
+const DEFAULT_BENCH_FRAMES: u64 = 600; // La mesure des chocs dure 10 secondes de jeu si on ne dit rien.
+
+#[derive(States, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)] // On prépare les outils pour les états.
+enum AppState { #[default] Loading, Title, Playing, Paused, GameOver, Victory, HighScoreEntry } // Les écrans du jeu (voir screens.rs), après le chargement des fichiers.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+struct InGame; // Vrai pendant toute une partie : en jeu, en pause, et sur "GAME OVER" ou "VICTOIRE".
+
+impl ComputedStates for InGame {
+    type SourceStates = AppState;
+
+    fn compute(state: AppState) -> Option<Self> {
+        matches!(state, AppState::Playing | AppState::Paused | AppState::GameOver | AppState::Victory).then_some(InGame)
+    }
+}
 
-const PLAYER_SPEED: f32 = 500.0; // La vitesse de notre vaisseau (il va vite !).
-const BULLET_SPEED: f32 = 700.0; // La vitesse des balles qui filent dans l'espace.
-const ENEMY_SPEED: f32 = 120.0; // La vitesse des méchants aliens qui descendent.
-const PLAYER_SIZE: Vec2 = Vec2::new(30.0, 15.0); // La taille du vaisseau du joueur.
-const ENEMY_SIZE: Vec2 = Vec2::new(25.0, 25.0); // La taille des petits aliens.
-const BULLET_SIZE: Vec2 = Vec2::new(5.0, 15.0); // La taille des projectiles.
-const PLAYER_HEALTH: i32 = 3; // Le nombre de vies (3 coeurs pour commencer).
-
-#[derive(States, Debug, Clone, PartialEq, Eq, Hash, Default)] // On prépare les outils pour les états.
-enum AppState { #[default] Running, Paused } // Le jeu peut être soit "En marche", soit "En pause".
-
-#[derive(Component)] struct Player; // Une étiquette pour dire : "Ça, c'est le joueur !".
-#[derive(Component)] struct Enemy { kind: EnemyType } // Une étiquette pour dire : "Ça, c'est un méchant !".
-#[derive(Component, PartialEq)] enum EnemyType { Soldier, Boss } // Il y a des petits soldats et des gros chefs.
-#[derive(Component)] struct Bullet { from_player: bool } // Une étiquette pour savoir qui a tiré la balle.
+// Tout ce qui appartient à une partie disparaît tout seul quand elle se termine (`DespawnOnExit<InGame>`).
+#[derive(Component)] #[require(Interpolated, DespawnOnExit<InGame>)] struct Player; // Une étiquette pour dire : "Ça, c'est le joueur !".
+#[derive(Component)] #[require(Interpolated, DespawnOnExit<InGame>)] struct Enemy { kind: EnemyType, armor: i32, health: i32 } // Une étiquette pour dire : "Ça, c'est un méchant !" (avec son armure et sa vie).
+#[derive(Component, Clone, Copy, Debug, PartialEq, Deserialize)] enum EnemyType { Soldier, Boss } // Il y a des petits soldats et des gros chefs.
+#[derive(Component)] #[require(Interpolated, DespawnOnExit<InGame>)] struct Bullet { from_player: bool } // Une étiquette pour savoir qui a tiré la balle.
 #[derive(Component)] struct Movable { velocity: Vec2 } // Une étiquette pour les objets qui peuvent bouger.
-#[derive(Component)] struct Health { current: i32 } // Une étiquette pour compter les points de vie.
 #[derive(Component)] struct EnemyFireTimer(Timer); // Un petit chrono pour que l'alien tire régulièrement.
-#[derive(Component)] struct Explosion { timer: Timer } // Un chrono pour que l'explosion disparaisse vite.
-#[derive(Component)] struct FloatingScore { timer: Timer } // Un chrono pour le petit texte "+10" qui monte.
+#[derive(Component)] #[require(DespawnOnExit<InGame>)] struct Explosion { timer: Timer } // Un chrono pour que l'explosion disparaisse vite.
+#[derive(Component)] #[require(DespawnOnExit<InGame>)] struct FloatingScore { timer: Timer } // Un chrono pour le petit texte "+10" qui monte.
 
 #[derive(Component)] struct ScoreText; // Étiquette pour le texte du score en haut.
+#[derive(Component)] struct HiScoreText; // Étiquette pour le record à battre, à côté du score.
 #[derive(Component)] struct LevelText; // Étiquette pour afficher le niveau actuel.
-#[derive(Component)] struct LivesText; // Étiquette pour afficher nos vies restantes.
 #[derive(Component)] struct MainMessage; // Étiquette pour les gros messages au milieu de l'écran.
 
-#[derive(Clone, Copy, Debug, PartialEq)] enum SpawnDirection { Top, Left, Right } // D'où viennent les aliens ?
-#[derive(Clone, Copy, Debug, PartialEq)] enum WaveState { Spawning, Fighting, LevelCompleted, Waiting } // Que font les aliens ?
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)] enum SpawnDirection { Top, Left, Right } // D'où viennent les aliens ?
+#[derive(Clone, Copy, Debug, PartialEq)] enum WaveState { Spawning, Fighting, ChallengeResults, LevelCompleted, Waiting } // Que font les aliens ?
 
 #[derive(Resource)] // Une ressource, c'est comme une boîte à outils partagée.
 struct WaveManager { // On crée le gestionnaire des vagues d'ennemis.
@@ -65,10 +126,17 @@ struct WaveManager { // On crée le gestionnaire des vagues d'ennemis.
     spawn_timer: Timer, // Le chrono entre chaque apparition d'alien.
     wave_timer: Timer, // Le chrono de repos entre deux vagues.
     show_good_job: bool, // Est-ce qu'on doit afficher "Bravo" ?
+    stage: u32, // Le numéro du stage depuis le début (stages bonus compris).
+    stage_kind: StageKind, // Stage normal ou stage bonus ?
+    results_timer: Timer, // Le chrono du tableau de fin du stage bonus.
 }
 
 impl Default for WaveManager { // On définit les réglages de départ de la boîte à outils.
-    fn default() -> Self { // C'est ici que tout commence à zéro.
+    fn default() -> Self { Self::new(&Tuning::default()) }
+}
+
+impl WaveManager {
+    fn new(tuning: &Tuning) -> Self { // C'est ici que tout commence à zéro, avec les chronos des réglages.
         Self {
             current_level: 1, // On commence au niveau 1.
             current_wave: 1, // On commence à la vague 1.
@@ -76,24 +144,25 @@ impl Default for WaveManager { // On définit les réglages de départ de la bo
             direction: SpawnDirection::Top, // Ils arrivent par le haut au début.
             enemies_spawned: 0, // Personne n'est encore apparu.
             enemies_killed_by_player: 0, // Tu n'as encore tué personne.
-            spawn_timer: Timer::from_seconds(0.6, TimerMode::Repeating), // Un alien toutes les 0.6 secondes.
-            wave_timer: Timer::from_seconds(2.0, TimerMode::Once), // 2 secondes de pause.
+            spawn_timer: Timer::from_seconds(tuning.spawn_interval, TimerMode::Repeating), // Un alien toutes les 0.6 secondes.
+            wave_timer: Timer::from_seconds(tuning.wave_pause, TimerMode::Once), // 2 secondes de pause.
             show_good_job: false, // On n'affiche pas encore le bravo.
+            stage: 1, // Le premier stage.
+            stage_kind: StageKind::Normal, // On commence par un stage normal.
+            results_timer: Timer::from_seconds(tuning.results_duration, TimerMode::Once), // 4 secondes pour lire le tableau du stage bonus.
         }
     }
 }
 
 #[derive(Resource, Default)] // Une autre ressource pour l'état général du jeu.
-struct GameState { // Le "cerveau" du jeu.
+struct GameState { // Le "cerveau" du jeu (perdu ou gagné, c'est l'écran en cours qui le dit : voir `AppState`).
     score: u32, // Ton score total.
-    game_over: bool, // Est-ce que tu as perdu ?
-    victory: bool, // Est-ce que tu as gagné ?
 }
 
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
 enum WeaponMode {
-    Single, DoubleJumelé, DoubleV, Triple, Quadruple, Quintuple, Sixtuple, Septuple,
+    Single, #[serde(alias = "DoubleJumele")] DoubleJumelé, DoubleV, Triple, Quadruple, Quintuple, Sixtuple, Septuple,
     Rapid2, Rapid3, Rapid4, Rapid5 // Balles l'une après l'autre
 }
 
@@ -104,7 +173,7 @@ struct PlayerStats {
     bullets_left_to_fire: u32,
 }
 
-#[derive(Component)]
+#[derive(Component, Clone, Debug, Deserialize)]
 enum BonusType {
     Weapon(WeaponMode),
     ExtraLife,
@@ -112,30 +181,131 @@ enum BonusType {
 }
 
 #[derive(Component)]
+#[require(Interpolated, DespawnOnExit<InGame>)]
 struct PowerUp {
     kind: BonusType,
 }
 
-fn main() { // La fonction principale : c'est le bouton "START" du code.
-    App::new() // On crée une nouvelle application de jeu.
-        .add_plugins(DefaultPlugins) // On installe tous les outils de base (sons, images, fenêtre).
-        .insert_resource(ClearColor(Color::BLACK)) // On peint le fond de l'espace en noir.
+fn main() -> AppExit { // La fonction principale : c'est le bouton "START" du code.
+    let options = CliOptions::parse(std::env::args().skip(1)).unwrap_or_else(|err| { // On lit les options du lancement.
+        eprintln!("{}\n{}", err, cli::USAGE);
+        std::process::exit(2);
+    });
+    let playback = options.replay.as_ref().map(|path| Recording::load(path).unwrap_or_else(|err| { // Le replay à rejouer, s'il y en a un.
+        eprintln!("{} : {}", path.display(), err);
+        std::process::exit(1);
+    }));
+    let seed = playback.as_ref().map(|r| r.seed).or(options.seed).unwrap_or_else(rand::random); // Sans graine demandée, on en choisit une (elle s'affichera à la fin).
+    if options.bench_collisions { return headless::bench_collisions(seed, options.frames.unwrap_or(DEFAULT_BENCH_FRAMES)); } // Juste la mesure, sans partie.
+    let replay = match (playback, options.record) {
+        (Some(recording), _) => Replay::Playback { recording, cursor: 0 },
+        (None, Some(path)) => Replay::Recording { path, recording: Recording { seed, tuning: None, frames: Vec::new() } },
+        (None, None) => Replay::Off,
+    };
+    let mut app = build_app(options.headless, seed, replay);
+    if options.headless { headless::run(app, options.frames.unwrap_or(DEFAULT_HEADLESS_FRAMES)) } // Sans fenêtre : on simule et on affiche le bilan.
+    else { app.run() } // On allume le moteur du jeu !
+}
+
+fn build_app(headless: bool, seed: u64, replay: Replay) -> App { // On assemble le jeu, avec ou sans fenêtre.
+    let mut app = App::new(); // On crée une nouvelle application de jeu.
+    let first_screen = if headless || replay.is_playback() { AppState::Playing } else { AppState::Title }; // Sans fenêtre ou en replay, personne n'appuie sur Espace : on joue tout de suite.
+    let mut status = StatusMessage::default();
+    let tuning = replay.recorded_tuning().cloned().unwrap_or_default(); // Un replay rejoue avec ses propres réglages.
+    let scores = match highscores::default_path() { // Sans fenêtre ou en replay, le tableau reste en mémoire : ces parties ne comptent pas.
+        Some(path) if !headless && !replay.is_playback() => {
+            let (scores, problem) = HighScores::load(path);
+            if let Some(problem) = problem { status.show(problem); }
+            scores
+        }
+        _ => HighScores::default(),
+    };
+    if headless {
+        app.add_plugins(headless::HeadlessPlugins); // Juste la simulation, sur une aire de jeu virtuelle.
+        headless::configure(&mut app);
+    } else {
+        app.add_plugins(DefaultPlugins) // On installe tous les outils de base (sons, images, fenêtre).
+            .add_systems(First, playfield::sync_playfield_system); // L'aire de jeu suit la taille de la fenêtre.
+    }
+    messages::add_messages(&mut app); // Les messages que la partie envoie à ceux qui l'écoutent.
+    app.insert_resource(ClearColor(Color::BLACK)) // On peint le fond de l'espace en noir.
+        .init_resource::<Playfield>() // La taille de l'écran.
+        .insert_resource(Time::<Fixed>::from_hz(interpolation::SIMULATION_HZ)) // La simulation avance 60 fois par seconde, quelle que soit la vitesse de l'écran.
+        .init_resource::<PlayerInput>() // Les commandes du joueur pour le prochain pas.
         .init_resource::<GameState>() // On prépare le cerveau du jeu.
+        .init_resource::<Lives>() // On prépare les vaisseaux de réserve.
+        .init_resource::<Sprites>() // Les images du pack choisi, et les dessins du jeu.
+        .init_resource::<Sky>() // Le ciel étoilé.
+        .init_resource::<Contacts>() // Les paires d'objets qui se touchent.
+        .init_resource::<GameStats>().init_resource::<Achievements>() // Les compteurs de la partie et les succès.
         .init_resource::<WaveManager>() // On prépare le chef des aliens.
-        .init_state::<AppState>() // On active le système de pause/marche.
-        .add_systems(Startup, setup_game) // On lance le système de départ une seule fois.
-        .add_systems(Update, (input_system, ui_update_system)) // On surveille le clavier et les textes tout le temps.
-        .add_systems(Update, ( // On lance tous ces systèmes seulement quand le jeu tourne.
-            player_control_system, player_shoot_system, // Bouger et tirer.
-            enemy_shoot_system, movement_system, wave_system, // Les aliens bougent, tirent et arrivent.
-            collision_system, cleanup_system // On gère les chocs et le nettoyage des objets.
-        ).run_if(in_state(AppState::Running))) // Tout ça s'arrête si on fait pause.
-        .run(); // On allume le moteur du jeu !
+        .init_resource::<Formation>() // On prépare la grille des aliens.
+        .init_resource::<DiveScheduler>() // On prépare le chrono des plongeons.
+        .insert_resource(GameRng::new(seed)) // On prépare le hasard de la partie.
+        .insert_resource(replay) // On enregistre la partie, on la rejoue, ou rien.
+        .insert_resource(tuning).insert_resource(status) // Les réglages, et le message qui dit quand ils changent.
+        .init_resource::<ScreenTimer>() // Le chrono des écrans de fin.
+        .insert_resource(scores).init_resource::<InitialsEntry>() // Le tableau des records, et les initiales qu'on tape.
+        .insert_state(AppState::Loading).add_computed_state::<InGame>() // On active les écrans du jeu, en commençant par le chargement.
+        .insert_resource(FirstScreen(first_screen)) // L'écran qui suit le chargement.
+        .init_asset::<LevelAsset>().init_asset::<CampaignAsset>() // Les niveaux sont des fichiers du dossier assets.
+        .init_asset_loader::<LevelLoader>().init_asset_loader::<CampaignLoader>() // Et voilà comment on les lit.
+        .init_asset::<Tuning>().init_asset_loader::<TuningLoader>() // Pareil pour le fichier de réglages.
+        .init_asset::<SpritePack>().init_asset_loader::<SpritePackLoader>() // Et pour les packs d'images.
+        .init_asset::<AsepriteSheet>().init_asset_loader::<AsepriteLoader>() // Et leurs planches Aseprite.
+        .add_systems(Startup, (setup_game, levels::load_campaign, tuning::load_tuning)) // On lance le système de départ une seule fois.
+        .add_systems(OnEnter(InGame), start_game) // Une nouvelle partie commence.
+        .add_systems(OnEnter(AppState::Title), screens::spawn_title_screen)
+        .add_systems(OnEnter(AppState::GameOver), screens::start_end_screen)
+        .add_systems(OnEnter(AppState::Victory), screens::start_end_screen)
+        .add_systems(OnEnter(AppState::HighScoreEntry), screens::spawn_scores_screen)
+        .add_systems(Update, (input_system, ui_update_system, lives::spare_ships_system, lives::fanfare_system)) // On surveille le clavier et les textes tout le temps.
+        .add_systems(Update, screens::scores_text_system.run_if(in_state(AppState::HighScoreEntry))) // Les initiales et le tableau des records.
+        .add_systems(Update, input::read_keyboard_system.run_if(|replay: Res<Replay>| !replay.is_playback())) // Pendant un replay, ce n'est pas le clavier qui pilote.
+        .add_systems(Update, (tuning::apply_tuning_system, tuning::level_reload_status_system, tuning::level_progress_system, tuning::status_text_system).chain()) // Les fichiers modifiés en jouant, même en pause.
+        .add_systems(Update, sprites::sprite_pack_system.after(tuning::apply_tuning_system)) // Le pack d'images choisi dans les réglages habille tout.
+        .add_systems(Update, screens::loading_done_system.after(sprites::sprite_pack_system) // Tout est chargé : on passe au premier écran.
+            .run_if(in_state(AppState::Loading).and(levels::levels_ready).and(tuning::tuning_ready).and(sprites::sprites_ready)))
+        .add_systems(Update, (animation::enemy_clip_system, animation::animation_system).chain().after(sprites::sprite_pack_system).run_if(not(in_state(AppState::Paused)))) // Les vignettes défilent, sauf en pause.
+        .add_systems(Update, (starfield::sky_system, starfield::starfield_system.run_if(not(in_state(AppState::Paused)))).chain()) // Le ciel du niveau défile, sauf en pause.
+        .add_systems(Update, collider::refit_colliders_system.after(tuning::apply_tuning_system).run_if(resource_changed::<Tuning>)) // Les zones de choc suivent les nouvelles tailles.
+        .add_systems(FixedFirst, interpolation::restore_simulation_system) // Avant chaque pas, on remet les vraies positions.
+        .add_systems(FixedLast, interpolation::record_simulation_system) // Après chaque pas, on les note.
+        .add_systems(RunFixedMainLoop, interpolation::interpolate_system.in_set(RunFixedMainLoopSystems::AfterFixedMainLoop)) // Après les pas, on dessine entre deux positions.
+        .add_systems(FixedPreUpdate, ( // Les commandes de ce pas : enregistrées ou lues dans le replay, puis la pause.
+            replay::replay_input_system, input::pause_system, screens::apply_state_system,
+        ).chain().run_if(not(in_state(AppState::Loading))))
+        .add_systems(FixedPostUpdate, (input::consume_presses_system, screens::apply_state_system).chain()) // Les appuis ont été vus par ce pas, et l'écran change tout de suite s'il le faut.
+        .add_systems(Last, replay::save_recording_system) // On écrit le replay en quittant.
+        .add_systems(FixedUpdate, ( // On lance tous ces systèmes à pas fixe, seulement quand le jeu tourne.
+            player_control_system.after(movement_system), player_shoot_system, // Bouger et tirer (le vaisseau est remis dans le cadre après le déplacement).
+            enemy_shoot_system, movement_system, wave_system.after(collision_system), // Les aliens bougent, tirent et arrivent (la vague voit les aliens abattus de ce pas).
+            ( // La grille bouge, certains aliens plongent, les autres suivent leur place.
+                formation::formation_system, dive::dive_scheduler_system, paths::follow_path_system,
+                dive::dive_end_system, tractor::tractor_park_system, tractor::tractor_beam_system,
+                formation::enemy_flight_system, tractor::captive_system,
+            ).chain(),
+            tractor::capture_system, // Le joueur aspiré par le rayon.
+            challenge::challenge_flyer_system, // Les acrobates du stage bonus qui ont fini leur figure.
+            collider::detect_contacts_system.after(movement_system).before(collision_system), // Qui touche qui, une fois pour tout le monde.
+            collision_system, cleanup_system, damage::hit_flash_system, // On gère les chocs, le nettoyage des objets et les aliens qui clignotent.
+            (lives::respawn_system, lives::invulnerability_system, lives::extra_ship_system), // Le vaisseau suivant, son clignotement, et les vaisseaux bonus.
+            ( // Ceux qui écoutent les messages des chocs et des vagues, dans le même pas.
+                messages::score_system, messages::effects_system, power_up_drop_system, power_up_system, lives::player_hit_system,
+                (stats::stats_system, stats::achievements_system).chain(),
+                sounds::sound_effects_system.run_if(resource_exists::<Assets<bevy::audio::Pitch>>),
+            ).after(wave_system),
+        ).run_if(in_state(AppState::Playing))) // Tout ça s'arrête si on fait pause ou si la partie est finie.
+        .add_systems(FixedUpdate, ( // Les écrans autour de la partie.
+            screens::title_system.run_if(in_state(AppState::Title)),
+            screens::end_screen_system.run_if(in_state(AppState::GameOver).or(in_state(AppState::Victory))),
+            screens::scores_screen_system.run_if(in_state(AppState::HighScoreEntry)),
+        ));
+    app
 }
 
-fn setup_game(mut commands: Commands, asset_server: Res<AssetServer>) { // La mise en place du décor.
+fn setup_game(mut commands: Commands) { // La mise en place du décor.
     commands.spawn(Camera2d); // On pose une caméra pour voir ce qui se passe.
-    spawn_player(&mut commands, &asset_server); // On fait apparaître ton vaisseau.
 
     commands.spawn(Node { // On crée une zone invisible en haut pour le texte.
         width: Val::Percent(100.0), // Ça prend toute la largeur.
@@ -146,7 +316,8 @@ fn setup_game(mut commands: Commands, asset_server: Res<AssetServer>) { // La mi
     }).with_children(|parent| { // On met des enfants (les textes) dans cette zone.
         parent.spawn((LevelText, Text::new(""), TextFont::from_font_size(20.0))); // Texte pour le niveau.
         parent.spawn((ScoreText, Text::new("Score: 0"), TextFont::from_font_size(25.0))); // Texte pour le score.
-        parent.spawn((LivesText, Text::new("Vies: 3"), TextFont::from_font_size(20.0))); // Texte pour les vies.
+        parent.spawn((HiScoreText, Text::new("HI-SCORE: 0"), TextFont::from_font_size(25.0), TextColor(Color::srgb(1.0, 0.3, 0.3)))); // Le record à battre, en rouge comme à l'arcade.
+        lives::spawn_spare_ships(parent); // Les vaisseaux de réserve.
     });
 
     commands.spawn(( // On crée le gros message au milieu.
@@ -160,116 +331,165 @@ fn setup_game(mut commands: Commands, asset_server: Res<AssetServer>) { // La mi
             ..default() // Le reste par défaut.
         }
     ));
+
+    commands.spawn(( // Le petit message en bas quand un fichier est rechargé.
+        StatusText,
+        Text::new(""),
+        TextFont::from_font_size(16.0),
+        TextColor(Color::srgb(1.0, 0.9, 0.3)), // En jaune, pour qu'on le remarque.
+        Node { position_type: PositionType::Absolute, left: Val::Px(15.0), bottom: Val::Px(10.0), ..default() },
+    ));
 }
 
-fn spawn_player(commands: &mut Commands, asset_server: &Res<AssetServer>) { // Fabriquer le vaisseau.
+fn spawn_player(commands: &mut Commands, sprites: &Sprites, tuning: &Tuning) -> Entity { // Fabriquer le vaisseau.
     commands.spawn(( // On crée l'entité du joueur.
         Player, // On lui met l'étiquette Joueur.
+        Collider::player(tuning, false), // Sa zone de choc.
         Movable { velocity: Vec2::ZERO }, // Il ne bouge pas encore.
-        Health { current: PLAYER_HEALTH }, // On lui donne ses 3 coeurs.
         PlayerStats { 
             weapon: WeaponMode::Single, 
             rapid_fire_timer: Timer::from_seconds(0.1, TimerMode::Repeating),
             bullets_left_to_fire: 0 
             },
+        Skin(SpriteId::Player), // Son rôle dans le pack d'images.
+        Animation::play(animation::THRUST), // La flamme de son réacteur.
         Sprite { // On lui donne une image.
-            image: asset_server.load("sprites/player_01.png"), // On charge l'image du vaisseau.
-            custom_size: Some(PLAYER_SIZE), // On règle sa taille.
-            ..default() // Le reste par défaut.
+            custom_size: Some(tuning.player_size), // On règle sa taille.
+            ..sprites.sprite(SpriteId::Player) // L'image du vaisseau, prise dans le pack.
         },
         Transform::from_xyz(0.0, -300.0, 1.0), // On le pose en bas de l'écran.
-    ));
+    )).id()
 }
 
 fn input_system( // Le système qui écoute tes doigts sur le clavier.
     kb: Res<ButtonInput<KeyCode>>, // On regarde quelles touches sont appuyées.
     mut exit: MessageWriter<AppExit>, // L'outil pour fermer le jeu.
-    state: Res<State<AppState>>, // On regarde si on est en pause.
-    mut next_state: ResMut<NextState<AppState>>, // L'outil pour changer de mode (pause/marche).
-    mut game_state: ResMut<GameState>, // L'outil pour modifier le cerveau du jeu.
-    mut wave_mgr: ResMut<WaveManager>, // L'outil pour modifier les vagues d'aliens.
-    mut commands: Commands, // L'outil pour donner des ordres.
-    asset_server: Res<AssetServer>, // L'outil pour charger les images.
-    entities_q: Query<Entity, Or<(With<Enemy>, With<Bullet>, With<Player>, With<Explosion>, With<FloatingScore>)>>, // On cherche tout le monde.
+    in_game: Option<Res<State<InGame>>>, // Est-ce qu'une partie est en cours ?
+    replay: Res<Replay>, // Un replay, on le regarde jusqu'au bout.
+    mut next_state: ResMut<NextState<AppState>>, // L'outil pour changer d'écran.
 ) {
     if kb.just_pressed(KeyCode::KeyQ) || kb.just_pressed(KeyCode::KeyX) || kb.just_pressed(KeyCode::Escape) { // Si tu appuies sur Q, X ou Echap...
         exit.write(AppExit::Success); // On ferme le jeu.
     }
 
-    if kb.just_pressed(KeyCode::KeyP) { // Si tu appuies sur P...
-        if *state.get() == AppState::Running { next_state.set(AppState::Paused); } // On met pause.
-        else { next_state.set(AppState::Running); } // Ou on remet en marche.
+    if kb.just_pressed(KeyCode::KeyR) && in_game.is_some() && !replay.is_playback() { // Si tu appuies sur R pendant une partie (pas pendant un replay)...
+        next_state.set(AppState::Title); // On abandonne : tout ce qui était à l'écran disparaît tout seul.
     }
+}
 
-    if kb.just_pressed(KeyCode::KeyR) { // Si tu appuies sur R (Recommencer)...
-        *game_state = GameState::default(); // On remet le cerveau à zéro.
-        *wave_mgr = WaveManager::default(); // On remet les vagues à zéro.
-        for entity in entities_q.iter() { // Pour chaque objet dans le jeu...
-            if let Ok(mut cmd) = commands.get_entity(entity) { cmd.despawn(); } // On le fait disparaître.
-        }
-        spawn_player(&mut commands, &asset_server); // On recrée un vaisseau tout neuf.
-        next_state.set(AppState::Running); // On relance le jeu.
-    }
+#[allow(clippy::too_many_arguments)]
+fn start_game( // Une nouvelle partie : tout repart de zéro.
+    mut commands: Commands,
+    sprites: Res<Sprites>,
+    tuning: Res<Tuning>,
+    mut game_state: ResMut<GameState>,
+    mut lives: ResMut<Lives>,
+    mut stats: ResMut<GameStats>,
+    mut wave_mgr: ResMut<WaveManager>,
+    mut formation: ResMut<Formation>,
+    mut dive_scheduler: ResMut<DiveScheduler>,
+    mut rng: ResMut<GameRng>,
+    mut replay: ResMut<Replay>,
+    mut input: ResMut<PlayerInput>,
+) {
+    *game_state = GameState::default(); // On remet le cerveau à zéro.
+    *lives = Lives::default(); // Tous les vaisseaux de réserve.
+    *stats = GameStats::default(); // Les compteurs repartent de zéro (les succès, eux, restent).
+    *wave_mgr = WaveManager::new(&tuning); // On remet les vagues à zéro.
+    *formation = Formation::default(); // On remet la grille à zéro.
+    *dive_scheduler = DiveScheduler::default(); // On remet le chrono des plongeons à zéro.
+    rng.restart(); // Même graine : chaque partie tire les mêmes nombres.
+    replay.restart(&tuning); // Le replay recommence avec la partie (et note ses réglages).
+    *input = PlayerInput::default(); // On oublie les appuis de l'écran titre.
+    spawn_player(&mut commands, &sprites, &tuning); // On fait apparaître ton vaisseau.
 }
 
+#[allow(clippy::too_many_arguments)]
 fn wave_system( // Le système qui gère l'arrivée des aliens.
     mut commands: Commands, // Pour faire apparaître les méchants.
-    asset_server: Res<AssetServer>, // Pour l'image de l'alien.
+    sprites: Res<Sprites>, // Pour l'image de l'alien.
     time: Res<Time>, // Pour compter le temps qui passe.
     mut wave_mgr: ResMut<WaveManager>, // Pour savoir où on en est dans les niveaux.
-    mut game_state: ResMut<GameState>, // Pour dire si on a gagné.
+    mut killed: MessageReader<EnemyKilled>, // Pour compter les aliens que tu as abattus.
+    mut cleared: MessageWriter<WaveCleared>, // Pour annoncer la fin d'une vague.
+    mut next_state: ResMut<NextState<AppState>>, // Pour dire si on a gagné.
+    mut formation: ResMut<Formation>, // Pour donner une place à chaque alien.
+    tuning: Res<Tuning>, // Pour la taille des aliens et le rythme d'arrivée par défaut.
+    mut rng: ResMut<GameRng>, // Pour tirer au sort la position des arrivées par le haut.
+    levels: Levels, // Les vagues décrites dans les fichiers de niveaux.
     enemy_q: Query<&Enemy>, // Pour compter combien il reste d'ennemis.
-    window_q: Query<&Window, With<PrimaryWindow>> // Pour connaître la taille de l'écran.
+    playfield: Res<Playfield>, // Pour connaître la taille de l'écran.
 ) {
-    let Ok(window) = window_q.single() else { return }; // Si on trouve la fenêtre, on continue.
+    let (Some(level_count), Some(level), Some(wave_def)) = ( // Tant que les fichiers de niveaux ne sont pas chargés, on attend.
+        levels.level_count(), levels.level(wave_mgr.current_level), levels.wave(wave_mgr.current_level, wave_mgr.current_wave),
+    ) else { return };
     let enemy_count = enemy_q.iter().count(); // On compte les aliens sur l'écran.
+    wave_mgr.enemies_killed_by_player += killed.read().count(); // Tes frags de ce pas.
 
-    wave_mgr.direction = match (wave_mgr.current_level, wave_mgr.current_wave) { // On choisit d'où ils viennent.
-        
-        (1, 1) => SpawnDirection::Left, // Niveau 1-1 : ils arrivent de gauche.
-        (1, 2) => SpawnDirection::Right, // Niveau 1-2 : ils arrivent de droite.
-        (1, 3) => SpawnDirection::Top, // Niveau 1-3 : ils arrivent de gauche.
-        
-        (2, 1) | (2, 3) | (3, 5) => SpawnDirection::Right, // D'autres niveaux de droite.
-        (2, 2) | (2, 4) | (3, 2) | (3, 4) => SpawnDirection::Left, // D'autres niveaux de gauche.
-        _ => SpawnDirection::Top, // Sinon, ils arrivent par le haut.
-    };
+    wave_mgr.direction = wave_def.direction; // Le fichier de niveau dit d'où ils viennent.
 
     match wave_mgr.state { // On regarde ce que la vague est en train de faire.
+        WaveState::Spawning if wave_mgr.stage_kind == StageKind::Challenging => { // Le stage bonus : les acrobates arrivent par groupes.
+            wave_mgr.spawn_timer.tick(time.delta());
+            if wave_mgr.spawn_timer.just_finished() && wave_mgr.enemies_spawned < challenge::FLYER_COUNT {
+                challenge::spawn_flyer(&mut commands, &sprites, &tuning, wave_mgr.enemies_spawned, &playfield);
+                wave_mgr.enemies_spawned += 1;
+                let delay = challenge::next_spawn_delay(wave_mgr.enemies_spawned); // Une pause entre deux groupes.
+                wave_mgr.spawn_timer.set_duration(std::time::Duration::from_secs_f32(delay));
+                if wave_mgr.enemies_spawned >= challenge::FLYER_COUNT { wave_mgr.state = WaveState::Fighting; }
+            }
+        },
         WaveState::Spawning => { // Ils sont en train d'arriver !
+            wave_mgr.spawn_timer.set_duration(std::time::Duration::from_secs_f32(wave_def.spawn_interval.unwrap_or(tuning.spawn_interval))); // Le rythme d'arrivée de cette vague.
             wave_mgr.spawn_timer.tick(time.delta()); // On fait avancer le chrono d'arrivée.
-            if wave_mgr.spawn_timer.just_finished() && wave_mgr.enemies_spawned < 10 { // Si le chrono dit "Go" et qu'on est moins de 10...
-                let is_boss = wave_mgr.enemies_spawned == 9; // Le 10ème alien est un Boss !
-                let sprite_path = if is_boss { "sprites/alien_red.png" } else { 
+            if wave_mgr.spawn_timer.just_finished() && wave_mgr.enemies_spawned < wave_def.size() { // Si le chrono dit "Go" et qu'il en reste à faire venir...
+                let Some(group) = wave_def.group_at(wave_mgr.enemies_spawned) else { return }; // Quel alien arrive maintenant ?
+                let is_boss = group.kind == EnemyType::Boss;
+                let sprite_id = if is_boss { SpriteId::Boss } else { 
                     match wave_mgr.direction {
-                        SpawnDirection::Left => "sprites/alien_red.png",
-                        SpawnDirection::Right => "sprites/alien_green.png",
-                        SpawnDirection::Top => "sprites/alien_grey.png",
+                        SpawnDirection::Left => SpriteId::SoldierLeft,
+                        SpawnDirection::Right => SpriteId::SoldierRight,
+                        SpawnDirection::Top => SpriteId::SoldierTop,
                     }
                 };
-                let (start_pos, velocity) = match wave_mgr.direction { // On calcule la position et la vitesse.
-                    SpawnDirection::Top => (Vec3::new((rand::random::<f32>() - 0.5) * window.width() * 0.8, window.height()/2.0 + 20.0, 0.0), Vec2::new(0.0, -ENEMY_SPEED)), // Arrivée par le haut.
-                    SpawnDirection::Left => (Vec3::new(-window.width()/2.0 - 20.0, 200.0, 0.0), Vec2::new(ENEMY_SPEED, -20.0)), // Arrivée par la gauche.
-                    SpawnDirection::Right => (Vec3::new(window.width()/2.0 + 20.0, 200.0, 0.0), Vec2::new(-ENEMY_SPEED, -20.0)), // Arrivée par la droite.
+                let start_pos = match wave_mgr.direction { // On calcule la position de départ.
+                    SpawnDirection::Top => Vec3::new((rng.spawns.gen::<f32>() - 0.5) * playfield.width() * 0.8, playfield.height()/2.0 + 20.0, 0.0), // Arrivée par le haut.
+                    SpawnDirection::Left => Vec3::new(-playfield.width()/2.0 - 20.0, 200.0, 0.0), // Arrivée par la gauche.
+                    SpawnDirection::Right => Vec3::new(playfield.width()/2.0 + 20.0, 200.0, 0.0), // Arrivée par la droite.
                 };
 
+                let enemy = Enemy::new(group.kind); // On définit son type.
+                let tint = enemy.tint(); // Sa couleur dépend de son armure.
                 commands.spawn(( // On fabrique l'alien !
-                    Enemy { kind: if is_boss { EnemyType::Boss } else { EnemyType::Soldier } }, // On définit son type.
-                    Movable { velocity }, // On lui donne sa vitesse.
-                    EnemyFireTimer(Timer::from_seconds(if is_boss { 1.2 } else { 2.5 }, TimerMode::Repeating)), // Son rythme de tir.
-                    Sprite { image: asset_server.load(sprite_path), custom_size: Some(if is_boss { ENEMY_SIZE * 2.5 } else { ENEMY_SIZE }), ..default() }, // Son image.
+                    enemy,
+                    Collider::enemy(&tuning, group.kind), // Sa zone de choc.
+                    formation.reserve_slot(is_boss), // On lui réserve sa place dans la grille.
+                    formation::entry_flight(wave_def.entry, wave_mgr.direction, start_pos.xy()), // Il vole jusqu'à sa place.
+                    EnemyFireTimer(Timer::from_seconds(group.fire_interval, TimerMode::Repeating)), // Son rythme de tir.
+                    Skin(sprite_id),
+                    Animation::play(animation::FLAP), // Il bat des ailes.
+                    Sprite { custom_size: Some(tuning.enemy_size_of(group.kind)), color: tint, ..sprites.sprite(sprite_id) }, // Son image.
                     
                     Transform::from_translation(start_pos) // On le place au point de départ.
                 ));
                 wave_mgr.enemies_spawned += 1; // On compte un alien de plus.
-                if wave_mgr.enemies_spawned >= 10 { wave_mgr.state = WaveState::Fighting; } // Quand il y en a 10, on passe au combat !
+                if wave_mgr.enemies_spawned >= wave_def.size() { wave_mgr.state = WaveState::Fighting; } // Quand ils sont tous là, on passe au combat !
             }
         },
         WaveState::Fighting => { // On est en plein combat !
-            if enemy_count == 0 { // Si tous les aliens sont morts...
-                wave_mgr.show_good_job = wave_mgr.enemies_killed_by_player >= 10; // On vérifie si tu as bien bossé.
-                if wave_mgr.current_wave >= 5 { // Si c'était la 5ème vague...
-                    if wave_mgr.current_level >= 3 { game_state.victory = true; } // Si c'était le niveau 3, tu as gagné le jeu !
+            let challenging = wave_mgr.stage_kind == StageKind::Challenging;
+            if enemy_count == 0 { // Plus personne : on l'annonce (les points du stage bonus viennent de là).
+                let (kills, size) = (wave_mgr.enemies_killed_by_player, if challenging { challenge::FLYER_COUNT } else { wave_def.size() });
+                cleared.write(WaveCleared { challenging, kills, perfect: kills >= size });
+            }
+            if enemy_count == 0 && challenging { // Fin du stage bonus : on compte les touches.
+                wave_mgr.show_good_job = false;
+                wave_mgr.state = WaveState::ChallengeResults;
+                wave_mgr.results_timer.reset();
+            } else if enemy_count == 0 { // Si tous les aliens sont morts...
+                wave_mgr.show_good_job = wave_mgr.enemies_killed_by_player >= wave_def.size(); // On vérifie si tu as bien bossé.
+                if wave_mgr.current_wave as usize >= level.waves.len() { // Si c'était la dernière vague du niveau...
+                    if wave_mgr.current_level as usize >= level_count { next_state.set(AppState::Victory); } // Si c'était le dernier niveau, tu as gagné le jeu !
                     else { wave_mgr.state = WaveState::LevelCompleted; wave_mgr.wave_timer.reset(); } // Sinon, niveau suivant.
                 } else { // Si c'était juste une petite vague...
                     wave_mgr.current_wave += 1; // Vague suivante.
@@ -278,31 +498,44 @@ fn wave_system( // Le système qui gère l'arrivée des aliens.
                 }
             }
         },
+        WaveState::ChallengeResults => { // On laisse le temps de lire le tableau.
+            wave_mgr.results_timer.tick(time.delta());
+            if wave_mgr.results_timer.is_finished() { // Puis on reprend la vague normale qui attendait.
+                wave_mgr.state = WaveState::Waiting;
+                wave_mgr.wave_timer.reset();
+            }
+        },
         WaveState::LevelCompleted | WaveState::Waiting => { // On attend entre deux vagues.
             wave_mgr.wave_timer.tick(time.delta()); // On fait avancer le chrono de pause.
             if wave_mgr.wave_timer.is_finished() { // Si le repos est fini...
                 if wave_mgr.state == WaveState::LevelCompleted { // Si on changeait de niveau...
+                    if wave_mgr.current_level as usize >= level_count { next_state.set(AppState::Victory); return; } // Plus de niveau après celui-ci : victoire !
                     wave_mgr.current_level += 1; // On passe au niveau +1.
                     wave_mgr.current_wave = 1; // On revient à la vague 1.
                 }
                 wave_mgr.enemies_spawned = 0; // On remet le compteur d'aliens à zéro.
                 wave_mgr.enemies_killed_by_player = 0; // On remet le compteur de tes frags à zéro.
+                formation.reset_slots(); // On libère les places de la grille.
+                wave_mgr.stage += 1; // Un stage de plus : est-ce le moment d'un stage bonus ?
+                wave_mgr.stage_kind = if challenge::is_challenging(wave_mgr.stage) { StageKind::Challenging } else { StageKind::Normal };
+                let delay = if wave_mgr.stage_kind == StageKind::Challenging { challenge::INTRO_DELAY } else { tuning.spawn_interval };
+                wave_mgr.spawn_timer = Timer::from_seconds(delay, TimerMode::Repeating);
                 wave_mgr.state = WaveState::Spawning; // Et on fait revenir des aliens !
             }
         }
     }
 }
 
-fn player_control_system(kb: Res<ButtonInput<KeyCode>>, window_q: Query<&Window, With<PrimaryWindow>>, mut query: Query<(&mut Movable, &mut Transform), With<Player>>, state: Res<GameState>) { // Contrôler ton vaisseau.
-    if state.game_over || state.victory { return; } // Si le jeu est fini, on ne bouge plus.
-    let Ok(window) = window_q.single() else { return }; // On regarde la taille de la fenêtre.
-    let limit = window.width() / 2.0 - PLAYER_SIZE.x / 2.0; // On calcule la limite pour ne pas sortir de l'écran.
-    if let Ok((mut movable, mut trans)) = query.single_mut() { // Si ton vaisseau existe...
+#[allow(clippy::type_complexity)]
+fn player_control_system(input: Res<PlayerInput>, tuning: Res<Tuning>, playfield: Res<Playfield>, mut query: Query<(&mut Movable, &mut Transform, Has<DualFighter>), (With<Player>, Without<BeingCaptured>)>) { // Contrôler ton vaisseau.
+    let limit = playfield.width() / 2.0 - tuning.player_size.x / 2.0; // On calcule la limite pour ne pas sortir de l'écran.
+    if let Ok((mut movable, mut trans, dual)) = query.single_mut() { // Si ton vaisseau existe...
+        let right_limit = if dual { limit - tuning.dual_offset() } else { limit }; // Le double chasseur est plus large à droite.
         let mut dir = 0.0; // On commence par ne pas bouger.
-        if kb.pressed(KeyCode::ArrowLeft) { dir -= 1.0; } // Flèche Gauche : on va vers la gauche.
-        if kb.pressed(KeyCode::ArrowRight) { dir += 1.0; } // Flèche Droite : on va vers la droite.
-        movable.velocity.x = dir * PLAYER_SPEED; // On donne la vitesse horizontale.
-        trans.translation.x = trans.translation.x.clamp(-limit, limit); // On t'empêche de sortir du cadre.
+        if input.left { dir -= 1.0; } // Flèche Gauche : on va vers la gauche.
+        if input.right { dir += 1.0; } // Flèche Droite : on va vers la droite.
+        movable.velocity.x = dir * tuning.player_speed; // On donne la vitesse horizontale.
+        trans.translation.x = trans.translation.x.clamp(-limit, right_limit); // On t'empêche de sortir du cadre.
     }
 }
 
@@ -315,59 +548,67 @@ fn movement_system(mut commands: Commands, mut query: Query<(Entity, &Movable, &
     }
 }
 
-fn enemy_shoot_system(mut commands: Commands, asset_server: Res<AssetServer>, time: Res<Time>, mut enemy_q: Query<(&Transform, &mut EnemyFireTimer)>, player_q: Query<&Transform, With<Player>>) { // Les aliens ripostent !
-    let Ok(p_trans) = player_q.single() else { return }; // On regarde où tu es pour te viser.
+fn enemy_shoot_system(mut commands: Commands, sprites: Res<Sprites>, tuning: Res<Tuning>, time: Res<Time>, mut enemy_q: Query<(&Transform, &mut EnemyFireTimer)>, player_q: Query<&Transform, With<Player>>) { // Les aliens ripostent !
+    let Ok(p_trans) = player_q.single() else { return }; // On regarde où tu es pour te viser (pas de vaisseau : personne ne tire).
     for (e_trans, mut timer) in enemy_q.iter_mut() { // Pour chaque alien...
         timer.0.tick(time.delta()); // On fait avancer son chrono de tir.
         if timer.0.just_finished() { // S'il doit tirer...
             let dir = (p_trans.translation - e_trans.translation).xy().normalize_or_zero(); // On vise ta direction.
             commands.spawn(( // On crée la balle alien.
                 Bullet { from_player: false }, // Elle vient d'un méchant.
-                Movable { velocity: dir * (ENEMY_SPEED * 1.8) }, // Elle fonce vers toi !
-                Sprite { image: asset_server.load("sprites/bullet_02.png"), custom_size: Some(BULLET_SIZE), color: Color::srgb(1.0, 0.0, 0.0), ..default() }, // Elle est rouge !
+                Collider::bullet(&tuning, false), // Elle peut toucher ton vaisseau.
+                Movable { velocity: dir * (tuning.enemy_speed * 1.8) }, // Elle fonce vers toi !
+                Skin(SpriteId::EnemyBullet),
+                Sprite { custom_size: Some(tuning.bullet_size), color: Color::srgb(1.0, 0.0, 0.0), ..sprites.sprite(SpriteId::EnemyBullet) }, // Elle est rouge !
                 Transform::from_translation(e_trans.translation), // Elle part de l'alien.
             ));
         }
     }
 }
+#[allow(clippy::type_complexity)]
 fn player_shoot_system(
     mut commands: Commands, 
-    asset_server: Res<AssetServer>, 
-    kb: Res<ButtonInput<KeyCode>>, 
-    mut query: Query<(&Transform, &mut PlayerStats), With<Player>>,
+    sprites: Res<Sprites>, 
+    tuning: Res<Tuning>,
+    input: Res<PlayerInput>,
+    mut query: Query<(&Transform, &mut PlayerStats, Has<DualFighter>), (With<Player>, Without<BeingCaptured>)>,
     time: Res<Time>,
 ) {
-    let Ok((transform, mut stats)) = query.single_mut() else { return };
+    let Ok((transform, mut stats, dual)) = query.single_mut() else { return };
     let base_pos = transform.translation + Vec3::new(0.0, 20.0, 0.0);
+    // Le double chasseur tire depuis ses deux vaisseaux
+    let muzzles = if dual { vec![base_pos, base_pos + Vec3::new(tuning.dual_offset(), 0.0, 0.0)] } else { vec![base_pos] };
 
     // Tir instantané au clic
-    if kb.just_pressed(KeyCode::Space) {
-        match stats.weapon {
-            WeaponMode::Single => spawn_bullet(&mut commands, &asset_server, base_pos, Vec2::new(0.0, BULLET_SPEED)),
-            WeaponMode::DoubleJumelé => {
-                spawn_bullet(&mut commands, &asset_server, base_pos + Vec3::new(-10.0, 0.0, 0.0), Vec2::new(0.0, BULLET_SPEED));
-                spawn_bullet(&mut commands, &asset_server, base_pos + Vec3::new(10.0, 0.0, 0.0), Vec2::new(0.0, BULLET_SPEED));
-            },
-            WeaponMode::DoubleV => {
-                spawn_bullet(&mut commands, &asset_server, base_pos, Vec2::new(-150.0, BULLET_SPEED));
-                spawn_bullet(&mut commands, &asset_server, base_pos, Vec2::new(150.0, BULLET_SPEED));
-            },
-            WeaponMode::Triple | WeaponMode::Quadruple | WeaponMode::Quintuple | WeaponMode::Sixtuple | WeaponMode::Septuple => {
-                let count = match stats.weapon {
-                    WeaponMode::Triple => 3, WeaponMode::Quadruple => 4,
-                    WeaponMode::Quintuple => 5, WeaponMode::Sixtuple => 6,
-                    _ => 7,
-                };
-                for i in 0..count {
-                    let step = i as f32 - (count as f32 - 1.0) / 2.0;
-                    spawn_bullet(&mut commands, &asset_server, base_pos, Vec2::new(step * 120.0, BULLET_SPEED));
+    if input.fire {
+        for &base_pos in &muzzles {
+            match stats.weapon {
+                WeaponMode::Single => spawn_bullet(&mut commands, &sprites, &tuning, base_pos, Vec2::new(0.0, tuning.bullet_speed)),
+                WeaponMode::DoubleJumelé => {
+                    spawn_bullet(&mut commands, &sprites, &tuning, base_pos + Vec3::new(-10.0, 0.0, 0.0), Vec2::new(0.0, tuning.bullet_speed));
+                    spawn_bullet(&mut commands, &sprites, &tuning, base_pos + Vec3::new(10.0, 0.0, 0.0), Vec2::new(0.0, tuning.bullet_speed));
+                },
+                WeaponMode::DoubleV => {
+                    spawn_bullet(&mut commands, &sprites, &tuning, base_pos, Vec2::new(-150.0, tuning.bullet_speed));
+                    spawn_bullet(&mut commands, &sprites, &tuning, base_pos, Vec2::new(150.0, tuning.bullet_speed));
+                },
+                WeaponMode::Triple | WeaponMode::Quadruple | WeaponMode::Quintuple | WeaponMode::Sixtuple | WeaponMode::Septuple => {
+                    let count = match stats.weapon {
+                        WeaponMode::Triple => 3, WeaponMode::Quadruple => 4,
+                        WeaponMode::Quintuple => 5, WeaponMode::Sixtuple => 6,
+                        _ => 7,
+                    };
+                    for i in 0..count {
+                        let step = i as f32 - (count as f32 - 1.0) / 2.0;
+                        spawn_bullet(&mut commands, &sprites, &tuning, base_pos, Vec2::new(step * 120.0, tuning.bullet_speed));
+                    }
+                },
+                // Prépare la rafale (bullets une après l'autre)
+                WeaponMode::Rapid2 | WeaponMode::Rapid3 | WeaponMode::Rapid4 | WeaponMode::Rapid5 => {
+                    stats.bullets_left_to_fire = match stats.weapon {
+                        WeaponMode::Rapid2 => 2, WeaponMode::Rapid3 => 3, WeaponMode::Rapid4 => 4, _ => 5,
+                    };
                 }
-            },
-            // Prépare la rafale (bullets une après l'autre)
-            WeaponMode::Rapid2 | WeaponMode::Rapid3 | WeaponMode::Rapid4 | WeaponMode::Rapid5 => {
-                stats.bullets_left_to_fire = match stats.weapon {
-                    WeaponMode::Rapid2 => 2, WeaponMode::Rapid3 => 3, WeaponMode::Rapid4 => 4, _ => 5,
-                };
             }
         }
     }
@@ -376,135 +617,163 @@ fn player_shoot_system(
     if stats.bullets_left_to_fire > 0 {
         stats.rapid_fire_timer.tick(time.delta());
         if stats.rapid_fire_timer.just_finished() {
-            spawn_bullet(&mut commands, &asset_server, base_pos, Vec2::new(0.0, BULLET_SPEED));
+            for &base_pos in &muzzles { spawn_bullet(&mut commands, &sprites, &tuning, base_pos, Vec2::new(0.0, tuning.bullet_speed)); }
             stats.bullets_left_to_fire -= 1;
         }
     }
 }
 
 // Fonction utilitaire indispensable
-fn spawn_bullet(commands: &mut Commands, asset_server: &Res<AssetServer>, pos: Vec3, vel: Vec2) {
+fn spawn_bullet(commands: &mut Commands, sprites: &Sprites, tuning: &Tuning, pos: Vec3, vel: Vec2) {
     commands.spawn((
         Bullet { from_player: true },
+        Collider::bullet(tuning, true),
         Movable { velocity: vel },
-        Sprite { image: asset_server.load("sprites/bullet_01.png"), custom_size: Some(BULLET_SIZE), ..default() },
+        Skin(SpriteId::PlayerBullet),
+        Sprite { custom_size: Some(tuning.bullet_size), ..sprites.sprite(SpriteId::PlayerBullet) },
         Transform::from_translation(pos),
     ));
 }
 
-fn collision_system(
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn collision_system( // Que font les paires qui se touchent (voir collider.rs) ? On l'annonce avec un message (voir messages.rs) : les points, les vies, les effets... c'est l'affaire de ceux qui écoutent.
     mut commands: Commands, 
-    mut state: ResMut<GameState>, 
-    mut wave_mgr: ResMut<WaveManager>,
-    bullet_q: Query<(Entity, &Transform, &Bullet)>, 
-    enemy_q: Query<(Entity, &Transform, &Enemy)>, 
-    mut player_q: Query<(Entity, &Transform, &mut Health, &mut PlayerStats), With<Player>>, 
-    powerup_q: Query<(Entity, &Transform, &PowerUp)>, // <--- IMPORTANT
-    asset_server: Res<AssetServer>,
+    contacts: Res<Contacts>,
+    sprites: Res<Sprites>, // Pour l'explosion du prisonnier.
+    mut enemy_q: Query<(&Transform, &mut Enemy, Option<&Flight>, Has<ChallengeFlyer>)>, 
+    captive_q: Query<&Transform, With<CapturedFighter>>, 
+    player_q: Query<(&Transform, Option<&DualFighter>, Has<BeingCaptured>, Has<Invulnerable>), With<Player>>, 
+    powerup_q: Query<&PowerUp>,
+    mut damaged: MessageWriter<EnemyDamaged>,
+    mut killed: MessageWriter<EnemyKilled>,
+    mut player_hit: MessageWriter<PlayerHit>,
+    mut collected: MessageWriter<PowerUpCollected>,
 ) {
-    let Ok((p_ent, p_trans, mut p_health, mut p_stats)) = player_q.single_mut() else { return };
-    let p_pos = p_trans.translation.xy();
-
     // 1. RAMASSAGE DES BONUS (Carrés jaunes)
-    for (pu_ent, pu_trans, pu_info) in powerup_q.iter() {
-        if p_pos.distance(pu_trans.translation.xy()) < 25.0 {
-            match pu_info.kind {
-                BonusType::Weapon(w) => p_stats.weapon = w, // Changement d'arme
-                BonusType::ExtraLife => p_health.current += 1, // +1 Vie
-                BonusType::NextLevel => { // Skip Level
-                    wave_mgr.state = WaveState::LevelCompleted;
-                    wave_mgr.wave_timer.reset();
-                }
-            }
-            commands.entity(pu_ent).despawn(); // Détruit le carré jaune
-        }
+    for (_, pu_ent) in contacts.between(Layer::Player, Layer::Pickup) {
+        let Ok(pu_info) = powerup_q.get(pu_ent) else { continue };
+        collected.write(PowerUpCollected { kind: pu_info.kind.clone() });
+        commands.entity(pu_ent).despawn(); // Détruit le carré jaune
     }
 
-    // 2. LOGIQUE EXISTANTE (Aliens et Balles)
-    for (e_ent, e_trans, e_info) in enemy_q.iter() {
-        let e_pos = e_trans.translation.xy();
-        let hit_radius = if e_info.kind == EnemyType::Boss { 50.0 } else { 25.0 };
-
-        // Si alien touche joueur
-        if p_pos.distance(e_pos) < hit_radius {
-            if let Ok(mut cmd) = commands.get_entity(e_ent) { cmd.despawn(); }
-            p_health.current -= 1;
-            spawn_explosion(&mut commands, &asset_server, p_trans.translation);
-            if p_health.current <= 0 { if let Ok(mut cmd) = commands.get_entity(p_ent) { cmd.despawn(); } state.game_over = true; }
+    // 2. LES BALLES DU JOUEUR CONTRE LES ALIENS (et le prisonnier d'un chef, qu'on détruit si on le touche)
+    let mut spent_bullets = Vec::new(); // Une balle ne touche qu'une seule cible, même là où le prisonnier et son chef se chevauchent.
+    let mut destroyed_captives = Vec::new(); // Une seule explosion, même si deux balles arrivent ensemble.
+    for (b_ent, e_ent) in contacts.between(Layer::PlayerBullet, Layer::Enemy) {
+        if spent_bullets.contains(&b_ent) { continue; } // Balle déjà utilisée.
+        if let Ok(c_trans) = captive_q.get(e_ent) { // Attention : tirer sur le prisonnier le détruit !
+            if destroyed_captives.contains(&e_ent) { continue; }
+            destroyed_captives.push(e_ent);
+            spent_bullets.push(b_ent);
+            spawn_explosion(&mut commands, &sprites, c_trans.translation);
+            commands.entity(e_ent).despawn();
+            if let Ok(mut cmd) = commands.get_entity(b_ent) { cmd.despawn(); }
+            continue;
         }
+        let Ok((e_trans, mut e_info, flight, _)) = enemy_q.get_mut(e_ent) else { continue };
+        if e_info.health <= 0 { continue; } // Déjà abattu par une autre balle.
+        spent_bullets.push(b_ent);
+        if let Ok(mut cmd) = commands.get_entity(b_ent) { cmd.despawn(); }
+        let diving = matches!(flight, Some(Flight::Diving));
+        if e_info.take_hit() == HitOutcome::ArmorCracked { // Il a encaissé.
+            damaged.write(EnemyDamaged { entity: e_ent, kind: e_info.kind, diving });
+            continue;
+        }
+        killed.write(EnemyKilled { kind: e_info.kind, position: e_trans.translation, diving });
+        if let Ok(mut cmd) = commands.get_entity(e_ent) { cmd.despawn(); }
+    }
 
-        for (b_ent, b_trans, b_type) in bullet_q.iter() {
-            let b_pos = b_trans.translation.xy();
-            if b_type.from_player && b_pos.distance(e_pos) < hit_radius {
-                // MORT D'UN ALIEN -> CHANCE DE BONUS
-                if rand::random::<f32>() < 0.2 { // 20% de chance
-                    let random_weapon = match rand::random::<u32>() % 8 {
-                        0 => WeaponMode::DoubleV, 1 => WeaponMode::Triple, 2 => WeaponMode::Septuple,
-                        3 => WeaponMode::Rapid3, 4 => WeaponMode::DoubleJumelé, _ => WeaponMode::Quintuple,
-                    };
-                    
-                    // On choisit au hasard entre arme, vie ou skip level
-                    let kind = match rand::random::<u32>() % 10 {
-                        0..=7 => BonusType::Weapon(random_weapon),
-                        8 => BonusType::ExtraLife,
-                        _ => BonusType::NextLevel,
-                    };
+    // 3. LE VAISSEAU TOUCHÉ : une seule fois par pas, même si plusieurs aliens ou balles arrivent ensemble
+    let Ok((p_trans, dual, captured, shielded)) = player_q.single() else { return };
+    if captured || shielded { return; } // Pas pendant qu'il se fait aspirer par le rayon, ni pendant qu'il clignote.
+    let rammer = contacts.between(Layer::Player, Layer::Enemy).find(|(_, e_ent)| // Un alien qui fonce dessus (jamais pendant le stage bonus).
+        enemy_q.get(*e_ent).is_ok_and(|(_, e_info, _, acrobat)| !acrobat && e_info.health > 0));
+    let shot = contacts.between(Layer::Player, Layer::EnemyBullet).next();
+    let Some((p_ent, culprit)) = rammer.or(shot) else { return }; // L'alien meurt dans le choc, la balle disparaît.
+    if let Ok(mut cmd) = commands.get_entity(culprit) { cmd.despawn(); }
+    player_hit.write(PlayerHit { ship: p_ent, position: p_trans.translation, wingman: dual.map(|dual| dual.wingman) });
+}
 
-                    commands.spawn((
-                        PowerUp { kind },
-                        Movable { velocity: Vec2::new(0.0, -150.0) },
-                        Sprite { color: Color::srgb(1.0, 1.0, 0.0), custom_size: Some(Vec2::splat(15.0)), ..default() },
-                        Transform::from_translation(e_trans.translation),
-                    ));
-                }
+fn power_up_drop_system( // Un alien abattu lâche parfois un bonus (la table vient du fichier de niveau).
+    mut commands: Commands,
+    mut killed: MessageReader<EnemyKilled>,
+    wave_mgr: Res<WaveManager>,
+    mut rng: ResMut<GameRng>,
+    levels: Levels,
+) {
+    for kill in killed.read() {
+        let drop = levels.drops(wave_mgr.current_level, wave_mgr.current_wave)
+            .and_then(|drops| drops.pick(rng.drops.gen::<f32>(), rng.drops.gen::<u32>()));
+        if let Some(kind) = drop {
+            commands.spawn((
+                PowerUp { kind },
+                Collider::pickup(),
+                Movable { velocity: Vec2::new(0.0, -150.0) },
+                Sprite { color: Color::srgb(1.0, 1.0, 0.0), custom_size: Some(Vec2::splat(15.0)), ..default() },
+                Transform::from_translation(kill.position),
+            ));
+        }
+    }
+}
 
-                state.score += if e_info.kind == EnemyType::Boss { 100 } else { 10 };
-                wave_mgr.enemies_killed_by_player += 1;
-                spawn_explosion(&mut commands, &asset_server, e_trans.translation);
-                if let Ok(mut cmd) = commands.get_entity(e_ent) { cmd.despawn(); }
-                if let Ok(mut cmd) = commands.get_entity(b_ent) { cmd.despawn(); }
-            } else if !b_type.from_player && b_pos.distance(p_pos) < 15.0 {
-                p_health.current -= 1;
-                spawn_explosion(&mut commands, &asset_server, p_trans.translation);
-                if let Ok(mut cmd) = commands.get_entity(b_ent) { cmd.despawn(); }
-                if p_health.current <= 0 { state.game_over = true; }
+fn power_up_system( // Le bonus attrapé fait son effet.
+    mut collected: MessageReader<PowerUpCollected>,
+    mut lives: ResMut<Lives>,
+    mut wave_mgr: ResMut<WaveManager>,
+    mut player_q: Query<&mut PlayerStats, With<Player>>,
+) {
+    for power_up in collected.read() {
+        match power_up.kind {
+            BonusType::Weapon(w) => { if let Ok(mut p_stats) = player_q.single_mut() { p_stats.weapon = w; } } // Changement d'arme
+            BonusType::ExtraLife => lives.ships += 1, // +1 Vie
+            BonusType::NextLevel => { // Skip Level
+                wave_mgr.state = WaveState::LevelCompleted;
+                wave_mgr.wave_timer.reset();
             }
         }
     }
 }
 
-
-
-fn spawn_explosion(commands: &mut Commands, asset_server: &Res<AssetServer>, pos: Vec3) { // Créer un feu d'artifice !
+fn spawn_explosion(commands: &mut Commands, sprites: &Sprites, pos: Vec3) { // Créer un feu d'artifice !
     commands.spawn((
         Explosion { timer: Timer::from_seconds(0.3, TimerMode::Once) }, // Ça dure 0.3 secondes.
-        Sprite { image: asset_server.load("sprites/explosion_01.png"), custom_size: Some(Vec2::splat(60.0)), ..default() }, // Image de l'explosion.
+        Skin(SpriteId::Explosion),
+        Animation::play(animation::BURST),
+        Sprite { custom_size: Some(Vec2::splat(60.0)), ..sprites.sprite(SpriteId::Explosion) }, // Image de l'explosion.
         Transform::from_translation(pos), // Là où ça a pété.
     ));
 }
 
-fn cleanup_system(mut commands: Commands, time: Res<Time>, mut explosion_q: Query<(Entity, &mut Explosion)>, mut score_q: Query<(Entity, &mut FloatingScore, &mut Transform)>) { // On nettoie ce qui est fini.
+fn cleanup_system(mut commands: Commands, time: Res<Time>, mut explosion_q: Query<(Entity, &mut Explosion)>, mut score_q: Query<(Entity, &mut FloatingScore, &mut Transform, &mut TextColor)>) { // On nettoie ce qui est fini.
     for (entity, mut explosion) in explosion_q.iter_mut() { // Pour chaque explosion...
         explosion.timer.tick(time.delta()); // On fait avancer son chrono.
         if explosion.timer.just_finished() { if let Ok(mut cmd) = commands.get_entity(entity) { cmd.despawn(); } } // Si fini, on l'enlève.
     }
-    for (entity, mut score, mut trans) in score_q.iter_mut() { // Pour chaque score flottant...
+    for (entity, mut score, mut trans, mut color) in score_q.iter_mut() { // Pour chaque score flottant...
         score.timer.tick(time.delta()); // On fait avancer son chrono.
         trans.translation.y += 1.5; // On le fait monter doucement vers le haut.
+        color.0.set_alpha(1.0 - score.timer.fraction()); // Et il s'efface petit à petit.
         if score.timer.just_finished() { if let Ok(mut cmd) = commands.get_entity(entity) { cmd.despawn(); } } // Si fini, on l'enlève.
     }
 }
 
-fn ui_update_system(state: Res<GameState>, wave_mgr: Res<WaveManager>, app_state: Res<State<AppState>>, player_q: Query<&Health, With<Player>>, mut text_queries: ParamSet<(Query<&mut Text, With<ScoreText>>, Query<&mut Text, With<LevelText>>, Query<&mut Text, With<LivesText>>, Query<&mut Text, With<MainMessage>>)>) { // Mettre à jour les textes.
+#[allow(clippy::type_complexity)]
+fn ui_update_system(state: Res<GameState>, scores: Res<HighScores>, wave_mgr: Res<WaveManager>, rng: Res<GameRng>, app_state: Res<State<AppState>>, levels: Levels, mut text_queries: ParamSet<(Query<&mut Text, With<ScoreText>>, Query<&mut Text, With<LevelText>>, Query<&mut Text, With<MainMessage>>, Query<&mut Text, With<HiScoreText>>)>) { // Mettre à jour les textes.
     if let Ok(mut text) = text_queries.p0().single_mut() { text.0 = format!("Score: {}", state.score); } // On affiche le nouveau score.
-    if let Ok(mut text) = text_queries.p1().single_mut() { text.0 = format!("Lvl: {} Wv: {}", wave_mgr.current_level, wave_mgr.current_wave); } // Le niveau et la vague.
-    let hp = player_q.single().map(|h| h.current).unwrap_or(0); // On regarde combien tu as de vies.
-    if let Ok(mut text) = text_queries.p2().single_mut() { text.0 = format!("Vies: {}", hp); } // On affiche tes vies.
-    if let Ok(mut text) = text_queries.p3().single_mut() { // On met à jour le gros message du milieu.
+    if let Ok(mut text) = text_queries.p3().single_mut() { text.0 = format!("HI-SCORE: {}", scores.best().max(state.score)); } // Le record monte en direct quand tu le bats.
+    if let Ok(mut text) = text_queries.p1().single_mut() { // Le niveau et la vague (ou le stage bonus).
+        text.0 = if wave_mgr.stage_kind == StageKind::Challenging { format!("Lvl: {} BONUS", wave_mgr.current_level) }
+            else { format!("Lvl: {} Wv: {}", wave_mgr.current_level, wave_mgr.current_wave) };
+    }
+    if let Ok(mut text) = text_queries.p2().single_mut() { // On met à jour le gros message du milieu.
         if *app_state.get() == AppState::Paused { text.0 = "PAUSE".to_string(); } // Si pause, on écrit "PAUSE".
-        else if state.game_over { text.0 = "GAME OVER".to_string(); } // Si perdu, on écrit "GAME OVER".
-        else if state.victory { text.0 = "VICTOIRE TOTALE !".to_string(); } // Si gagné, on écrit "VICTOIRE".
+        else if *app_state.get() == AppState::GameOver { text.0 = format!("GAME OVER\nSeed: {}", rng.seed); } // Si perdu, on écrit "GAME OVER" et la graine pour rejouer la partie.
+        else if *app_state.get() == AppState::Victory { text.0 = "VICTOIRE TOTALE !".to_string(); } // Si gagné, on écrit "VICTOIRE".
+        else if *app_state.get() != AppState::Playing { text.0 = "".to_string(); } // Le titre et les scores ont leurs propres textes.
+        else if wave_mgr.state == WaveState::ChallengeResults { text.0 = challenge::results_message(wave_mgr.enemies_killed_by_player); } // Le tableau du stage bonus.
+        else if wave_mgr.stage_kind == StageKind::Challenging && wave_mgr.state == WaveState::Spawning && wave_mgr.enemies_spawned == 0 { text.0 = "CHALLENGING STAGE".to_string(); } // L'annonce du stage bonus.
         else if wave_mgr.state == WaveState::LevelCompleted { text.0 = format!("LEVEL {} RÉUSSI !", wave_mgr.current_level); } // Si niveau fini.
+        else if let Some(level) = levels.level(wave_mgr.current_level).filter(|_| wave_mgr.current_wave == 1 && wave_mgr.state == WaveState::Spawning && wave_mgr.enemies_spawned < 3) { text.0 = level.name.clone(); } // Le nom du niveau qui commence.
         else if wave_mgr.state == WaveState::Waiting && wave_mgr.show_good_job { text.0 = "Good Job !!!".to_string(); } // Si tu as bien tué tout le monde.
         else { text.0 = "".to_string(); } // Sinon, on n'écrit rien.
     }
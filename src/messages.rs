@@ -0,0 +1,100 @@
+// ═══════════════════════════════════════════════════════════════════════════
+// 📣 LES MESSAGES DU JEU : "un alien est mort", "le vaisseau est touché"...
+// ═══════════════════════════════════════════════════════════════════════════
+//
+// Avant, `collision_system` faisait tout lui-même : il ajoutait les points,
+// enlevait les vies, comptait les aliens abattus, faisait les explosions et
+// lâchait les bonus. Pour ajouter quoi que ce soit, il fallait le modifier.
+//
+// Maintenant, les chocs et les vagues se contentent d'annoncer ce qui s'est
+// passé avec un message Bevy :
+//
+//   collision_system ──> EnemyDamaged, EnemyKilled, PlayerHit, PowerUpCollected
+//   wave_system ───────> WaveCleared
+//
+// et chaque partie du jeu écoute ceux qui l'intéressent dans son propre
+// système : les points (ici), les effets (ici), les sons (sounds.rs), les
+// statistiques et les succès (stats.rs), les vies (lives.rs), les bonus
+// (main.rs). Une nouvelle idée = un nouveau système qui écoute, sans toucher
+// aux chocs. Les écouteurs tournent juste après `wave_system`, dans le même pas.
+
+use bevy::prelude::*;
+
+use crate::damage::{self, HitOutcome};
+use crate::sprites::Sprites;
+use crate::{challenge, spawn_explosion, BonusType, EnemyType, FloatingScore, GameState};
+
+const POPUP_DURATION: f32 = 0.8; // Le temps pendant lequel "+10" monte et s'efface.
+const BONUS_POPUP_DURATION: f32 = 2.0; // Le bonus du stage bonus reste plus longtemps.
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum PopupStyle { Normal, Diving, Boss, Bonus } // L'allure du score qui s'envole.
+
+#[derive(Message, Clone, Copy, Debug)]
+pub struct EnemyDamaged { pub entity: Entity, pub kind: EnemyType, pub diving: bool } // Un tir du joueur a cassé l'armure d'un alien.
+
+#[derive(Message, Clone, Copy, Debug)]
+pub struct EnemyKilled { pub kind: EnemyType, pub position: Vec3, pub diving: bool } // Un tir du joueur a abattu un alien.
+
+#[derive(Message, Clone, Copy, Debug)]
+pub struct PlayerHit { pub ship: Entity, pub position: Vec3, pub wingman: Option<Entity> } // Le vaisseau est touché (avec un double chasseur, c'est l'ailier qui part).
+
+#[derive(Message, Clone, Debug)]
+pub struct PowerUpCollected { pub kind: BonusType } // Le vaisseau a attrapé un bonus.
+
+#[derive(Message, Clone, Copy, Debug)]
+pub struct WaveCleared { pub challenging: bool, pub kills: usize, pub perfect: bool } // Plus un seul alien : la vague (ou le stage bonus) est finie.
+
+pub fn add_messages(app: &mut App) { // On déclare tous les messages du jeu.
+    app.add_message::<EnemyDamaged>()
+        .add_message::<EnemyKilled>()
+        .add_message::<PlayerHit>()
+        .add_message::<PowerUpCollected>()
+        .add_message::<WaveCleared>();
+}
+
+pub fn score_system( // Les points : chaque tir qui porte, et le bonus du stage bonus.
+    mut damaged: MessageReader<EnemyDamaged>,
+    mut killed: MessageReader<EnemyKilled>,
+    mut cleared: MessageReader<WaveCleared>,
+    mut state: ResMut<GameState>,
+) {
+    for hit in damaged.read() { state.score += damage::score_for_hit(&hit.kind, HitOutcome::ArmorCracked, hit.diving); }
+    for kill in killed.read() { state.score += damage::score_for_hit(&kill.kind, HitOutcome::Destroyed, kill.diving); }
+    for wave in cleared.read().filter(|wave| wave.challenging) { state.score += challenge::bonus(wave.kills); }
+}
+
+pub fn effects_system( // Ce qu'on voit : l'alien abîmé clignote, l'alien abattu explose et ses points s'envolent.
+    mut commands: Commands,
+    sprites: Res<Sprites>,
+    mut damaged: MessageReader<EnemyDamaged>,
+    mut killed: MessageReader<EnemyKilled>,
+    mut cleared: MessageReader<WaveCleared>,
+) {
+    for hit in damaged.read() { damage::flash(&mut commands, hit.entity); }
+    for kill in killed.read() {
+        spawn_explosion(&mut commands, &sprites, kill.position);
+        let points = damage::score_for_hit(&kill.kind, HitOutcome::Destroyed, kill.diving); // Les mêmes points que `score_system`.
+        let style = if kill.kind == EnemyType::Boss { PopupStyle::Boss } else if kill.diving { PopupStyle::Diving } else { PopupStyle::Normal };
+        spawn_floating_score(&mut commands, format!("+{}", points), kill.position, style);
+    }
+    for wave in cleared.read().filter(|wave| wave.challenging) { // Le total du stage bonus, au milieu de l'écran.
+        spawn_floating_score(&mut commands, format!("BONUS +{}", challenge::bonus(wave.kills)), Vec3::new(0.0, -60.0, 0.0), PopupStyle::Bonus);
+    }
+}
+
+fn spawn_floating_score(commands: &mut Commands, text: String, position: Vec3, style: PopupStyle) { // Un score qui monte en s'effaçant (voir `cleanup_system`).
+    let (color, size, duration) = match style {
+        PopupStyle::Normal => (Color::WHITE, 16.0, POPUP_DURATION),
+        PopupStyle::Diving => (Color::srgb(1.0, 0.75, 0.2), 20.0, POPUP_DURATION), // Orange : il rapporte le double.
+        PopupStyle::Boss => (Color::srgb(1.0, 0.35, 1.0), 26.0, POPUP_DURATION * 1.5), // Magenta et gros : un chef !
+        PopupStyle::Bonus => (Color::srgb(0.4, 1.0, 0.5), 34.0, BONUS_POPUP_DURATION),
+    };
+    commands.spawn((
+        FloatingScore { timer: Timer::from_seconds(duration, TimerMode::Once) },
+        Text2d::new(text),
+        TextFont::from_font_size(size),
+        TextColor(color),
+        Transform::from_translation(position.with_z(5.0)), // Devant les aliens et les explosions.
+    ));
+}
@@ -0,0 +1,176 @@
+// ═══════════════════════════════════════════════════════════════════════════
+// 〰️ LES TRAJECTOIRES : des courbes que les aliens peuvent suivre
+// ═══════════════════════════════════════════════════════════════════════════
+//
+// `Movable` ne sait aller qu'en ligne droite. Ici on décrit des chemins courbes
+// (Bézier ou Catmull-Rom) et un composant `PathFollower` qui fait avancer un
+// objet le long du chemin à vitesse constante.
+
+use bevy::prelude::*;
+
+const LENGTH_SAMPLES: usize = 64; // Le nombre de petits morceaux pour mesurer la longueur d'une courbe.
+
+#[derive(Clone, Debug)]
+pub enum Spline { // Une courbe décrite par des points.
+    Bezier(Vec<Vec2>), // Des morceaux de Bézier cubique : départ, contrôle, contrôle, arrivée, contrôle, contrôle, arrivée...
+    CatmullRom(Vec<Vec2>), // Une courbe qui passe par tous les points.
+}
+
+impl Spline {
+    pub fn sample(&self, t: f32) -> Vec2 { // La position sur la courbe, avec t entre 0 (début) et 1 (fin).
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Spline::Bezier(points) => {
+                if points.len() < 4 { return points.first().copied().unwrap_or_default(); }
+                let (seg, local) = split(t, (points.len() - 1) / 3);
+                let i = seg * 3;
+                cubic_bezier(points[i], points[i + 1], points[i + 2], points[i + 3], local)
+            }
+            Spline::CatmullRom(points) => {
+                if points.len() < 2 { return points.first().copied().unwrap_or_default(); }
+                let (seg, local) = split(t, points.len() - 1);
+                let p1 = points[seg];
+                let p2 = points[seg + 1];
+                let p0 = if seg == 0 { p1 } else { points[seg - 1] }; // On double les extrémités pour que la courbe parte bien du premier point.
+                let p3 = points.get(seg + 2).copied().unwrap_or(p2);
+                catmull_rom(p0, p1, p2, p3, local)
+            }
+        }
+    }
+}
+
+fn split(t: f32, segments: usize) -> (usize, f32) { // Dans quel morceau tombe t, et où dans ce morceau ?
+    let scaled = t * segments as f32;
+    let seg = (scaled.floor() as usize).min(segments - 1);
+    (seg, scaled - seg as f32)
+}
+
+pub fn quadratic_bezier(p0: Vec2, p1: Vec2, p2: Vec2, t: f32) -> Vec2 { // Une courbe douce qui passe près du point de contrôle.
+    let u = 1.0 - t;
+    p0 * (u * u) + p1 * (2.0 * u * t) + p2 * (t * t)
+}
+
+fn cubic_bezier(p0: Vec2, p1: Vec2, p2: Vec2, p3: Vec2, t: f32) -> Vec2 {
+    let u = 1.0 - t;
+    p0 * (u * u * u) + p1 * (3.0 * u * u * t) + p2 * (3.0 * u * t * t) + p3 * (t * t * t)
+}
+
+fn catmull_rom(p0: Vec2, p1: Vec2, p2: Vec2, p3: Vec2, t: f32) -> Vec2 {
+    let t2 = t * t;
+    let t3 = t2 * t;
+    0.5 * ((2.0 * p1) + (p2 - p0) * t + (2.0 * p0 - 5.0 * p1 + 4.0 * p2 - p3) * t2 + (3.0 * p1 - p0 - 3.0 * p2 + p3) * t3)
+}
+
+#[derive(Component)]
+pub struct PathFollower { // Un objet qui avance le long d'une courbe.
+    spline: Spline, // La courbe à suivre.
+    lengths: Vec<f32>, // La distance parcourue à chaque petit morceau (pour garder une vitesse constante).
+    speed: f32, // La vitesse en pixels par seconde.
+    travelled: f32, // La distance déjà parcourue.
+}
+
+impl PathFollower {
+    pub fn new(spline: Spline, speed: f32) -> Self {
+        let mut lengths = Vec::with_capacity(LENGTH_SAMPLES + 1);
+        let mut total = 0.0;
+        let mut previous = spline.sample(0.0);
+        lengths.push(0.0);
+        for i in 1..=LENGTH_SAMPLES { // On mesure la courbe petit bout par petit bout.
+            let point = spline.sample(i as f32 / LENGTH_SAMPLES as f32);
+            total += previous.distance(point);
+            lengths.push(total);
+            previous = point;
+        }
+        Self { spline, lengths, speed, travelled: 0.0 }
+    }
+
+    pub fn length(&self) -> f32 { *self.lengths.last().unwrap_or(&0.0) }
+
+    pub fn finished(&self) -> bool { self.travelled >= self.length() }
+
+    pub fn scale_speed(&mut self, factor: f32) { self.speed *= factor; } // Les réglages ont changé en plein vol : il garde sa place sur la courbe.
+
+    pub fn position(&self) -> Vec2 { // On retrouve le t qui correspond à la distance parcourue.
+        let d = self.travelled.min(self.length());
+        let i = self.lengths.partition_point(|l| *l < d).clamp(1, LENGTH_SAMPLES);
+        let (a, b) = (self.lengths[i - 1], self.lengths[i]);
+        let local = if b > a { (d - a) / (b - a) } else { 0.0 };
+        self.spline.sample((i as f32 - 1.0 + local) / LENGTH_SAMPLES as f32)
+    }
+}
+
+pub fn follow_path_system(time: Res<Time>, mut query: Query<(&mut PathFollower, &mut Transform)>) { // On fait avancer tout ce qui suit une courbe.
+    for (mut follower, mut trans) in query.iter_mut() {
+        follower.travelled += follower.speed * time.delta_secs();
+        trans.translation = follower.position().extend(trans.translation.z);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const S_CURVE: [Vec2; 7] = [ // Deux morceaux de Bézier : un S, avec un virage serré.
+        Vec2::new(0.0, 0.0), Vec2::new(0.0, 200.0), Vec2::new(150.0, 200.0), Vec2::new(150.0, 0.0),
+        Vec2::new(150.0, -30.0), Vec2::new(300.0, -30.0), Vec2::new(300.0, 100.0),
+    ];
+
+    fn close(a: Vec2, b: Vec2) -> bool { a.distance(b) < 1e-3 }
+
+    #[test]
+    fn bezier_starts_joins_and_ends_on_its_points() {
+        let spline = Spline::Bezier(S_CURVE.to_vec());
+        assert!(close(spline.sample(0.0), S_CURVE[0]));
+        assert!(close(spline.sample(0.5), S_CURVE[3]), "le raccord entre les deux morceaux");
+        assert!(close(spline.sample(1.0), S_CURVE[6]));
+        assert!(close(spline.sample(7.0), S_CURVE[6]) && close(spline.sample(-1.0), S_CURVE[0]), "t est ramené entre 0 et 1");
+    }
+
+    #[test]
+    fn catmull_rom_goes_through_every_point() {
+        let spline = Spline::CatmullRom(S_CURVE.to_vec());
+        for (i, point) in S_CURVE.iter().enumerate() {
+            assert!(close(spline.sample(i as f32 / 6.0), *point), "point {} : {}", i, spline.sample(i as f32 / 6.0));
+        }
+    }
+
+    #[test]
+    fn too_short_curves_stay_put() {
+        assert_eq!(Spline::Bezier(vec![Vec2::ONE, Vec2::X]).sample(0.7), Vec2::ONE);
+        assert_eq!(Spline::CatmullRom(vec![Vec2::ONE]).sample(0.7), Vec2::ONE);
+        assert_eq!(Spline::CatmullRom(Vec::new()).sample(0.7), Vec2::ZERO);
+    }
+
+    #[test]
+    fn the_follower_keeps_a_constant_speed() {
+        let mut follower = PathFollower::new(Spline::Bezier(S_CURVE.to_vec()), 300.0);
+        let step = 300.0 / 60.0;
+        let mut previous = follower.position();
+        let mut steps = Vec::new();
+        while !follower.finished() {
+            follower.travelled += step;
+            let position = follower.position();
+            steps.push(previous.distance(position));
+            previous = position;
+        }
+        steps.pop(); // Le dernier pas s'arrête au bout de la courbe.
+        assert!(steps.len() > 100, "{} pas", steps.len());
+        assert!(steps.iter().all(|d| (d - step).abs() < step * 0.05), "la même distance à chaque pas, même dans le virage : {:?}", steps);
+        assert!(close(previous, S_CURVE[6]));
+    }
+
+    #[test]
+    fn a_straight_path_measures_its_length() {
+        let follower = PathFollower::new(Spline::CatmullRom(vec![Vec2::ZERO, Vec2::new(0.0, -300.0)]), 100.0);
+        assert!((follower.length() - 300.0).abs() < 0.01);
+        assert!(!follower.finished());
+    }
+
+    #[test]
+    fn a_new_speed_keeps_the_place_on_the_curve() {
+        let mut follower = PathFollower::new(Spline::CatmullRom(vec![Vec2::ZERO, Vec2::new(0.0, -300.0)]), 100.0);
+        follower.travelled = 50.0;
+        follower.scale_speed(2.0);
+        assert_eq!((follower.speed, follower.travelled), (200.0, 50.0));
+    }
+}
@@ -0,0 +1,31 @@
+// ═══════════════════════════════════════════════════════════════════════════
+// 🖼️ L'AIRE DE JEU : la taille de l'écran, avec ou sans fenêtre
+// ═══════════════════════════════════════════════════════════════════════════
+//
+// Les aliens arrivent depuis les bords, la grille s'accroche en haut et le
+// vaisseau ne doit pas sortir sur les côtés : tout ça dépend de la taille de
+// l'écran. Avant, chaque système demandait la fenêtre, et sans fenêtre plus
+// rien ne bougeait. Maintenant, ils lisent `Playfield` : avec une fenêtre, elle
+// suit sa taille ; en mode sans fenêtre, elle garde une taille virtuelle.
+
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+pub const DEFAULT_SIZE: Vec2 = Vec2::new(1280.0, 720.0); // La taille d'une fenêtre Bevy par défaut.
+
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct Playfield { pub size: Vec2 } // La taille de l'aire de jeu, en pixels.
+
+impl Default for Playfield {
+    fn default() -> Self { Self { size: DEFAULT_SIZE } }
+}
+
+impl Playfield {
+    pub fn width(&self) -> f32 { self.size.x }
+    pub fn height(&self) -> f32 { self.size.y }
+}
+
+pub fn sync_playfield_system(window_q: Query<&Window, With<PrimaryWindow>>, mut playfield: ResMut<Playfield>) { // L'aire de jeu suit la fenêtre.
+    let Ok(window) = window_q.single() else { return };
+    if playfield.size != window.size() { playfield.size = window.size(); }
+}
@@ -0,0 +1,175 @@
+// ═══════════════════════════════════════════════════════════════════════════
+// 📼 LES REPLAYS : on enregistre les commandes, et on rejoue la même partie
+// ═══════════════════════════════════════════════════════════════════════════
+//
+// Grâce à la graine du hasard et au pas fixe, une partie ne dépend plus que
+// des commandes du joueur à chaque pas. Il suffit donc d'enregistrer ces
+// commandes (gauche, droite, tir, pause) et la graine pour pouvoir rejouer la
+// partie à l'identique : pratique pour reproduire un bug ou montrer un beau score.
+//
+//   cargo run -- --record partie.xgr    (enregistre, le fichier est écrit en quittant)
+//   cargo run -- --replay partie.xgr    (rejoue ; le clavier ne pilote plus le vaisseau)
+//
+// Le fichier est petit : une en-tête, puis les commandes compressées en
+// "tant de pas identiques d'affilée".
+//
+//   "XGRP"            4 octets, pour reconnaître le fichier
+//   version           1 octet (REPLAY_VERSION)
+//   graine            8 octets, little-endian
+//   réglages          4 octets (longueur, little-endian) puis le texte RON
+//                     des réglages de la partie ; longueur 0 : ceux du fichier
+//   puis des paquets  1 octet de commandes + 2 octets (little-endian) de répétitions
+//
+// Les réglages voyagent avec le replay : on rejoue avec ceux de la partie,
+// même si `tuning.tuning.ron` a changé depuis. Pendant un enregistrement, le
+// jeu refuse donc de les recharger en cours de route.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use bevy::prelude::*;
+
+use crate::input::PlayerInput;
+use crate::tuning::{StatusMessage, Tuning};
+
+const MAGIC: &[u8; 4] = b"XGRP"; // Les premiers octets d'un fichier de replay.
+pub const REPLAY_VERSION: u8 = 2; // À augmenter si le format change.
+const HEADER_LEN: usize = 4 + 1 + 8 + 4; // Sans le texte des réglages.
+
+// Une commande par bit.
+const LEFT: u8 = 1 << 0;
+const RIGHT: u8 = 1 << 1;
+const FIRE: u8 = 1 << 2;
+const PAUSE: u8 = 1 << 3;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Recording { pub seed: u64, pub tuning: Option<Tuning>, pub frames: Vec<u8> } // La graine, les réglages de la partie et les commandes de chaque pas.
+
+#[derive(Debug)]
+pub enum ReplayError { // Tout ce qui peut mal se passer avec un fichier de replay.
+    Io(std::io::Error),
+    NotAReplay, // Le fichier ne commence pas par "XGRP".
+    UnsupportedVersion(u8), // Un fichier d'une autre version du jeu.
+    Truncated, // Le fichier s'arrête au milieu d'un paquet.
+    BadTuning(String), // Les réglages de l'en-tête ne se lisent pas.
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::Io(err) => write!(f, "impossible de lire le replay : {}", err),
+            ReplayError::NotAReplay => write!(f, "ce fichier n'est pas un replay"),
+            ReplayError::UnsupportedVersion(v) => write!(f, "replay en version {} (ce jeu lit la version {})", v, REPLAY_VERSION),
+            ReplayError::Truncated => write!(f, "le replay est coupé"),
+            ReplayError::BadTuning(err) => write!(f, "les réglages du replay sont illisibles : {}", err),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+impl From<std::io::Error> for ReplayError {
+    fn from(err: std::io::Error) -> Self { ReplayError::Io(err) }
+}
+
+impl PlayerInput {
+    fn to_bits(self) -> u8 { // Les commandes d'un pas tiennent dans un octet.
+        (self.left as u8 * LEFT) | (self.right as u8 * RIGHT) | (self.fire as u8 * FIRE) | (self.pause as u8 * PAUSE)
+    }
+
+    fn from_bits(bits: u8) -> Self {
+        Self { left: bits & LEFT != 0, right: bits & RIGHT != 0, fire: bits & FIRE != 0, pause: bits & PAUSE != 0 }
+    }
+}
+
+impl Recording {
+    pub fn encode(&self) -> Vec<u8> { // On écrit l'en-tête, puis les commandes par paquets de pas identiques.
+        let mut bytes = Vec::with_capacity(HEADER_LEN + self.frames.len() / 8);
+        bytes.extend_from_slice(MAGIC);
+        bytes.push(REPLAY_VERSION);
+        bytes.extend_from_slice(&self.seed.to_le_bytes());
+        let tuning = self.tuning.as_ref().map(|t| ron::to_string(t).expect("les réglages s'écrivent en RON")).unwrap_or_default();
+        bytes.extend_from_slice(&(tuning.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(tuning.as_bytes());
+        for run in self.frames.chunk_by(|a, b| a == b) {
+            for part in run.chunks(u16::MAX as usize) { // Un paquet compte au plus 65535 pas.
+                bytes.push(part[0]);
+                bytes.extend_from_slice(&(part.len() as u16).to_le_bytes());
+            }
+        }
+        bytes
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self, ReplayError> {
+        if bytes.len() < MAGIC.len() || &bytes[..MAGIC.len()] != MAGIC { return Err(ReplayError::NotAReplay); }
+        if bytes.len() < HEADER_LEN { return Err(ReplayError::Truncated); }
+        if bytes[4] != REPLAY_VERSION { return Err(ReplayError::UnsupportedVersion(bytes[4])); }
+        let seed = u64::from_le_bytes(bytes[5..13].try_into().expect("8 octets"));
+        let tuning_len = u32::from_le_bytes(bytes[13..HEADER_LEN].try_into().expect("4 octets")) as usize;
+        let text = bytes.get(HEADER_LEN..HEADER_LEN + tuning_len).ok_or(ReplayError::Truncated)?;
+        let tuning = match tuning_len {
+            0 => None,
+            _ => Some(std::str::from_utf8(text).ok().and_then(|text| ron::from_str(text).ok()).ok_or_else(|| ReplayError::BadTuning(String::from_utf8_lossy(text).into_owned()))?),
+        };
+        let runs = bytes[HEADER_LEN + tuning_len..].chunks(3);
+        let mut frames = Vec::new();
+        for run in runs {
+            let [bits, lo, hi] = run else { return Err(ReplayError::Truncated) };
+            frames.extend(std::iter::repeat_n(*bits, u16::from_le_bytes([*lo, *hi]) as usize));
+        }
+        Ok(Self { seed, tuning, frames })
+    }
+
+    pub fn load(path: &Path) -> Result<Self, ReplayError> { Self::decode(&std::fs::read(path)?) }
+
+    pub fn push(&mut self, input: PlayerInput) { self.frames.push(input.to_bits()); } // On ajoute les commandes d'un pas.
+}
+
+#[derive(Resource)]
+pub enum Replay { // Est-ce qu'on enregistre, qu'on rejoue, ou rien du tout ?
+    Off,
+    Recording { path: PathBuf, recording: Recording },
+    Playback { recording: Recording, cursor: usize },
+}
+
+impl Replay {
+    pub fn is_playback(&self) -> bool { matches!(self, Replay::Playback { .. }) }
+
+    pub fn is_recording(&self) -> bool { matches!(self, Replay::Recording { .. }) }
+
+    pub fn recorded_tuning(&self) -> Option<&Tuning> { // Les réglages avec lesquels on rejoue (None : ceux du fichier).
+        match self { Replay::Playback { recording, .. } => recording.tuning.as_ref(), _ => None }
+    }
+
+    pub fn restart(&mut self, tuning: &Tuning) { // Une nouvelle partie : on réenregistre depuis le début (avec ses réglages), ou on rejoue depuis le début.
+        match self {
+            Replay::Off => {}
+            Replay::Recording { recording, .. } => { recording.frames.clear(); recording.tuning = Some(tuning.clone()); }
+            Replay::Playback { cursor, .. } => *cursor = 0,
+        }
+    }
+}
+
+pub fn replay_input_system(mut replay: ResMut<Replay>, mut input: ResMut<PlayerInput>, mut status: ResMut<StatusMessage>) { // À chaque pas : on note les commandes, ou on les lit dans le replay.
+    match replay.as_mut() {
+        Replay::Off => {}
+        Replay::Recording { recording, .. } => recording.push(*input),
+        Replay::Playback { recording, cursor } => {
+            *input = match recording.frames.get(*cursor) {
+                Some(&bits) => PlayerInput::from_bits(bits),
+                None => PlayerInput::default(), // Le replay est fini : plus personne ne pilote.
+            };
+            *cursor += 1;
+            if *cursor == recording.frames.len() { status.show("Fin du replay".to_string()); }
+        }
+    }
+}
+
+pub fn save_recording_system(mut exit: MessageReader<AppExit>, replay: Res<Replay>) { // Quand on quitte, on écrit le fichier.
+    if exit.read().next().is_none() { return; }
+    let Replay::Recording { path, recording } = replay.as_ref() else { return };
+    match std::fs::write(path, recording.encode()) {
+        Ok(()) => info!("Replay enregistré : {} ({} pas, graine {})", path.display(), recording.frames.len(), recording.seed),
+        Err(err) => error!("Impossible d'écrire le replay {} : {}", path.display(), err),
+    }
+}
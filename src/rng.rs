@@ -0,0 +1,41 @@
+// ═══════════════════════════════════════════════════════════════════════════
+// 🎲 LE HASARD REPRODUCTIBLE : une graine, et les mêmes tirages à chaque fois
+// ═══════════════════════════════════════════════════════════════════════════
+//
+// Avant, les bonus, les arrivées par le haut et les plongeons tiraient au sort
+// avec `rand::random` : impossible de rejouer deux fois la même partie. Maintenant
+// tout le hasard vient d'une seule graine. Chaque usage a son propre flux
+// (arrivées, bonus, cerveau des aliens), pour qu'un tirage de plus d'un côté ne
+// décale pas tous les autres. Même graine + mêmes touches = même partie.
+//
+// La graine se choisit avec `--seed 1234` et s'affiche sur l'écran GAME OVER.
+
+use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+// Chaque flux mélange la graine avec son propre sel, pour ne pas tirer les mêmes nombres que les autres.
+const SPAWNS_SALT: u64 = 0x5350_4157_4e53; // "SPAWNS"
+const DROPS_SALT: u64 = 0x0044_524f_5053; // "DROPS"
+const AI_SALT: u64 = 0x4149; // "AI"
+
+#[derive(Resource)]
+pub struct GameRng { // Le hasard du jeu, rangé par flux.
+    pub seed: u64, // La graine de la partie.
+    pub spawns: StdRng, // D'où arrivent les aliens.
+    pub drops: StdRng, // Les bonus lâchés par les aliens.
+    pub ai: StdRng, // Qui plonge, et comment.
+}
+
+impl GameRng {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            spawns: StdRng::seed_from_u64(seed ^ SPAWNS_SALT),
+            drops: StdRng::seed_from_u64(seed ^ DROPS_SALT),
+            ai: StdRng::seed_from_u64(seed ^ AI_SALT),
+        }
+    }
+
+    pub fn restart(&mut self) { *self = Self::new(self.seed); } // On recommence avec la même graine : la partie se rejoue à l'identique.
+}
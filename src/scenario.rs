@@ -0,0 +1,725 @@
+// ═══════════════════════════════════════════════════════════════════════════
+// 🧪 LES SCÉNARIOS DE TEST : on monte une situation, on simule, on vérifie
+// ═══════════════════════════════════════════════════════════════════════════
+//
+// Un scénario construit le vrai jeu sans fenêtre (voir headless.rs), vide
+// l'écran, puis pose exactement ce qu'on veut : "le joueur en x=0, un chef en
+// y=100, le joueur tire deux fois". On avance de N pas de simulation et on
+// regarde le score, les vies, l'état de la vague et ce qui reste à l'écran.
+//
+// Pendant un scénario, la vague est en combat : `wave_system` ne fait rien
+// tant qu'il reste des aliens, et passe à la suite quand ils sont tous morts.
+// Les commandes du joueur passent par un replay qu'on écrit au fur et à
+// mesure, exactement comme un replay enregistré.
+
+use std::path::PathBuf;
+
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::input::ButtonState;
+use bevy::prelude::*;
+
+use crate::challenge::{self, ChallengeFlyer, StageKind};
+use crate::collider::Collider;
+use crate::formation::{Flight, Formation, FormationSlot};
+use crate::input::PlayerInput;
+use crate::interpolation::Interpolated;
+use crate::levels::{Campaign, CampaignAsset, LevelAsset};
+use crate::paths::{PathFollower, Spline};
+use crate::replay::{Recording, Replay};
+use crate::screens::FirstScreen;
+use crate::sprites::Sprites;
+use crate::starfield::Star;
+use crate::tractor::{CapturedFighter, DualFighter, RescuedFighter, TractorDive};
+use crate::tuning::Tuning;
+use crate::highscores::HighScores;
+use crate::lives::{Invulnerable, Lives};
+use crate::stats::{Achievement, Achievements, GameStats};
+use crate::damage::{self, HitOutcome};
+use crate::{build_app, headless, spawn_player, AppState, BonusType, Bullet, Enemy, EnemyType, FloatingScore, GameState, InGame, Movable, Player, PowerUp, WaveManager, WaveState, PLAYER_LIVES};
+
+const SEED: u64 = 1; // Tous les scénarios tirent le même hasard.
+const PLAYER_Y: f32 = -300.0; // La hauteur du vaisseau, comme dans `spawn_player`.
+
+pub struct Scenario { app: App, input: PlayerInput } // Le jeu, et les commandes que le joueur tient en ce moment.
+
+impl Scenario {
+    pub fn new() -> Self { // Le jeu chargé, l'écran vide, la vague en combat, pas encore de joueur.
+        let mut app = build_app(true, SEED, Replay::Playback { recording: Recording { seed: SEED, tuning: None, frames: Vec::new() }, cursor: 0 });
+        assert!(headless::wait_until_loaded(&mut app), "les fichiers du jeu ne se chargent pas");
+        let world = app.world_mut();
+        let leftovers: Vec<Entity> = world
+            .query_filtered::<Entity, With<DespawnOnExit<InGame>>>()
+            .iter(world).collect();
+        for entity in leftovers { world.despawn(entity); }
+        let tuning = world.resource::<Tuning>().clone();
+        *world.resource_mut::<GameState>() = GameState::default();
+        *world.resource_mut::<Lives>() = Lives::default();
+        *world.resource_mut::<Formation>() = Formation::default();
+        let mut wave_mgr = WaveManager::new(&tuning);
+        wave_mgr.state = WaveState::Fighting;
+        *world.resource_mut::<WaveManager>() = wave_mgr;
+        Self { app, input: PlayerInput::default() }
+    }
+
+    pub fn player_at(mut self, x: f32) -> Self { // Le vaisseau du joueur, en bas de l'écran.
+        self.app.world_mut().run_system_cached(|mut commands: Commands, sprites: Res<Sprites>, tuning: Res<Tuning>| {
+            spawn_player(&mut commands, &sprites, &tuning);
+        }).expect("spawn_player");
+        let world = self.app.world_mut();
+        let mut trans = world.query_filtered::<&mut Transform, With<Player>>().single_mut(world).expect("un seul joueur");
+        trans.translation.x = x;
+        self
+    }
+
+    pub fn enemy(mut self, kind: EnemyType, at: Vec2) -> Self { self.spawn_enemy(kind, at, None); self } // Un alien immobile.
+
+    pub fn diving_enemy(mut self, kind: EnemyType, at: Vec2) -> Self { self.spawn_enemy(kind, at, Some(Flight::Diving)); self } // Un alien en piqué.
+
+    fn spawn_enemy(&mut self, kind: EnemyType, at: Vec2, flight: Option<Flight>) -> Entity {
+        let collider = Collider::enemy(self.app.world().resource::<Tuning>(), kind);
+        let mut enemy = self.app.world_mut().spawn((Enemy::new(kind), collider, Transform::from_translation(at.extend(0.0))));
+        if let Some(flight) = flight { enemy.insert(flight); }
+        enemy.id()
+    }
+
+    pub fn player_bullet_at(&mut self, at: Vec2) -> &mut Self { // Une balle du joueur posée là, immobile.
+        let collider = Collider::bullet(self.app.world().resource::<Tuning>(), true);
+        self.app.world_mut().spawn((Bullet { from_player: true }, collider, Movable { velocity: Vec2::ZERO }, Transform::from_translation(at.extend(0.0))));
+        self
+    }
+
+    pub fn boss_untouched(&mut self) -> bool { // Le premier chef à l'écran a-t-il encore toute son armure ?
+        let world = self.app.world_mut();
+        world.query::<&Enemy>().iter(world).find(|enemy| enemy.kind == EnemyType::Boss).is_some_and(|enemy| enemy.armor == EnemyType::Boss.max_armor())
+    }
+
+    pub fn challenging_stage(mut self) -> Self { // Le stage 3 commence : "CHALLENGING STAGE", puis les acrobates.
+        let mut wave_mgr = self.app.world_mut().resource_mut::<WaveManager>();
+        (wave_mgr.stage, wave_mgr.stage_kind, wave_mgr.state) = (3, StageKind::Challenging, WaveState::Spawning);
+        wave_mgr.spawn_timer = Timer::from_seconds(challenge::INTRO_DELAY, TimerMode::Repeating);
+        self
+    }
+
+    pub fn flyers(&mut self) -> Vec<(EnemyType, Vec2)> { // Les acrobates du stage bonus, et où ils sont.
+        let world = self.app.world_mut();
+        world.query_filtered::<(&Enemy, &Transform), With<ChallengeFlyer>>().iter(world).map(|(enemy, trans)| (enemy.kind, trans.translation.xy())).collect()
+    }
+
+    pub fn enemy_bullets(&mut self) -> usize { // Combien de tirs aliens sont en route ?
+        let world = self.app.world_mut();
+        world.query::<&Bullet>().iter(world).filter(|bullet| !bullet.from_player).count()
+    }
+
+    pub fn tractor_boss(mut self, at: Vec2) -> Self { // Un chef garé au-dessus de `at`, qui allume son rayon au prochain pas.
+        let boss = self.spawn_enemy(EnemyType::Boss, at, Some(Flight::Diving));
+        self.app.world_mut().entity_mut(boss).insert((TractorDive, FormationSlot { col: 1, row: 0 }, PathFollower::new(Spline::CatmullRom(vec![at]), 100.0)));
+        self
+    }
+
+    pub fn enemy_bullet(mut self, at: Vec2, velocity: Vec2) -> Self { // Un tir alien déjà en route.
+        let collider = Collider::bullet(self.app.world().resource::<Tuning>(), false);
+        self.app.world_mut().spawn((Bullet { from_player: false }, collider, Movable { velocity }, Transform::from_translation(at.extend(0.0))));
+        self
+    }
+
+    pub fn power_up(mut self, kind: BonusType, at: Vec2) -> Self { // Un bonus immobile.
+        self.app.world_mut().spawn((PowerUp { kind }, Collider::pickup(), Transform::from_translation(at.extend(0.0))));
+        self
+    }
+
+    pub fn fire(&mut self) -> &mut Self { self.input.fire = true; self } // Le joueur appuie sur Espace au prochain pas.
+
+    pub fn pause(&mut self) -> &mut Self { self.input.pause = true; self } // Le joueur appuie sur P au prochain pas.
+
+    pub fn hold(&mut self, left: bool, right: bool) -> &mut Self { self.input.left = left; self.input.right = right; self } // Le joueur tient (ou lâche) les flèches.
+
+    pub fn step(&mut self, ticks: u32) -> &mut Self { // On avance de `ticks` pas de simulation.
+        for _ in 0..ticks {
+            if let Replay::Playback { recording, .. } = self.app.world_mut().resource_mut::<Replay>().as_mut() { recording.push(self.input); }
+            self.input.fire = false; // Un appui ne dure qu'un pas.
+            self.input.pause = false;
+            self.app.update();
+        }
+        self
+    }
+
+    pub fn score(&self) -> u32 { self.app.world().resource::<GameState>().score }
+
+    pub fn high_scores(&self) -> &HighScores { self.app.world().resource::<HighScores>() }
+
+    pub fn popups(&mut self) -> Vec<(String, f32)> { // Les scores qui s'envolent : leur texte et leur taille.
+        let world = self.app.world_mut();
+        world.query_filtered::<(&Text2d, &TextFont), With<FloatingScore>>().iter(world).map(|(text, font)| (text.0.clone(), font.font_size)).collect()
+    }
+
+    pub fn player_image_size(&mut self) -> Option<UVec2> { // La taille en pixels de l'image du vaisseau, si elle est prête.
+        let world = self.app.world_mut();
+        let handle = world.query_filtered::<&Sprite, With<Player>>().single(world).ok()?.image.clone();
+        world.resource::<Assets<Image>>().get(&handle).map(|image| image.size())
+    }
+
+    pub fn player_frame(&mut self) -> Option<usize> { // La vignette que montre le vaisseau en ce moment.
+        let world = self.app.world_mut();
+        world.query_filtered::<&Sprite, With<Player>>().single(world).ok()?.texture_atlas.as_ref().map(|atlas| atlas.index)
+    }
+
+    pub fn stats(&self) -> &GameStats { self.app.world().resource::<GameStats>() }
+
+    pub fn achievements(&self) -> &[Achievement] { &self.app.world().resource::<Achievements>().unlocked }
+
+    pub fn screen(&self) -> AppState { *self.app.world().resource::<State<AppState>>().get() }
+
+    pub fn wave_state(&self) -> WaveState { self.app.world().resource::<WaveManager>().state }
+
+    pub fn progress(&self) -> (u32, u32) { let wave_mgr = self.app.world().resource::<WaveManager>(); (wave_mgr.current_level, wave_mgr.current_wave) } // Le niveau et la vague.
+
+    pub fn at_wave(mut self, level: u32, wave: u32) -> Self { // On reprend plus loin dans la campagne.
+        let mut wave_mgr = self.app.world_mut().resource_mut::<WaveManager>();
+        wave_mgr.current_level = level;
+        wave_mgr.current_wave = wave;
+        self
+    }
+
+    pub fn shorten_campaign(&mut self, levels: usize, waves: usize) -> &mut Self { // Comme si on enregistrait des fichiers de niveaux plus courts en jouant.
+        let world = self.app.world_mut();
+        let campaign = world.resource::<Campaign>().0.clone();
+        let handles = world.resource_mut::<Assets<CampaignAsset>>().get_mut(&campaign).map(|c| { c.levels.truncate(levels); c.levels.clone() }).unwrap_or_default();
+        let mut assets = world.resource_mut::<Assets<LevelAsset>>();
+        for handle in handles { if let Some(level) = assets.get_mut(&handle) { level.waves.truncate(waves); } }
+        self
+    }
+
+    pub fn lives(&self) -> i32 { self.app.world().resource::<Lives>().ships } // Les vaisseaux qui restent, celui qui vole compris.
+
+    pub fn with_score(mut self, score: u32) -> Self { // On reprend une partie déjà bien avancée.
+        self.app.world_mut().resource_mut::<GameState>().score = score;
+        self
+    }
+
+    pub fn regroup(&mut self) -> &mut Self { // Tous les aliens reprennent leur place dans la grille.
+        let world = self.app.world_mut();
+        for mut flight in world.query::<&mut Flight>().iter_mut(world) { *flight = Flight::InFormation; }
+        self
+    }
+
+    pub fn ram(mut self) -> Self { // Un soldat fonce pile sur le vaisseau, qui perd une vie.
+        let x = self.player_x().expect("un vaisseau à percuter");
+        self = self.enemy(EnemyType::Soldier, Vec2::new(x, PLAYER_Y));
+        self.step(1);
+        self
+    }
+
+    pub fn player_x(&mut self) -> Option<f32> {
+        let world = self.app.world_mut();
+        world.query_filtered::<(&Transform, &Interpolated), With<Player>>().iter(world).next()
+            .map(|(trans, interp)| interp.simulated().unwrap_or(trans.translation).x) // La position de la simulation, pas celle dessinée.
+    }
+
+    pub fn star_drift(&mut self, ticks: u32) -> f32 { // De combien les étoiles descendent en `ticks` pas, en moyenne.
+        let heights = |app: &mut App| { let world = app.world_mut(); world.query_filtered::<&Transform, With<Star>>().iter(world).map(|trans| trans.translation.y).collect::<Vec<_>>() };
+        let before = heights(&mut self.app);
+        self.step(ticks);
+        let drifts: Vec<f32> = before.iter().zip(heights(&mut self.app)).map(|(y, after)| y - after).filter(|&drift| drift >= 0.0).collect(); // Celles revenues en haut ne comptent pas.
+        drifts.iter().sum::<f32>() / drifts.len() as f32
+    }
+
+    pub fn positions<T: Component>(&mut self) -> Vec<Vec2> { // Où sont-ils dans la simulation (pas là où ils sont dessinés) ?
+        let world = self.app.world_mut();
+        world.query_filtered::<(&Transform, &Interpolated), With<T>>().iter(world).map(|(trans, interp)| interp.simulated().unwrap_or(trans.translation).xy()).collect()
+    }
+
+    pub fn step_until(&mut self, ticks: u32, mut done: impl FnMut(&mut Self) -> bool) -> bool { // On avance jusqu'à ce que `done` (au plus `ticks` pas).
+        for _ in 0..ticks {
+            if done(self) { return true; }
+            self.step(1);
+        }
+        done(self)
+    }
+
+    pub fn edit_tuning(&mut self, edit: impl Fn(&mut Tuning)) -> &mut Self { edit_tuning_file(&mut self.app, edit); self } // On enregistre le fichier de réglages en jouant.
+
+    pub fn bullet_velocities(&mut self) -> Vec<(bool, Vec2)> { // Les tirs en route : du joueur ou pas, et leur vitesse.
+        let world = self.app.world_mut();
+        world.query::<(&Bullet, &Movable)>().iter(world).map(|(bullet, movable)| (bullet.from_player, movable.velocity)).collect()
+    }
+
+    pub fn count<T: Component>(&mut self) -> usize { // Combien d'entités portent ce composant ?
+        let world = self.app.world_mut();
+        world.query_filtered::<(), With<T>>().iter(world).count()
+    }
+}
+
+const BULLET_FLIGHT: u32 = 45; // Assez de pas pour qu'une balle du joueur monte de y=-280 à y=100 (700 px/s, 60 pas/s).
+
+#[test]
+fn soldier_kill_scores_10() {
+    let mut s = Scenario::new().player_at(0.0).enemy(EnemyType::Soldier, Vec2::new(0.0, 100.0));
+    s.fire().step(BULLET_FLIGHT);
+    assert_eq!(s.score(), 10);
+    assert_eq!(s.count::<Enemy>(), 0);
+    assert_eq!(s.count::<Bullet>(), 0, "la balle disparaît avec l'alien");
+}
+
+#[test]
+fn boss_takes_two_shots_and_scores_100_on_the_kill() {
+    let mut s = Scenario::new().player_at(0.0).enemy(EnemyType::Boss, Vec2::new(0.0, 100.0));
+    s.fire().step(BULLET_FLIGHT);
+    assert_eq!(s.score(), 10, "le premier tir casse l'armure : un dixième des points");
+    assert_eq!(s.count::<Enemy>(), 1);
+    s.fire().step(BULLET_FLIGHT);
+    assert_eq!(s.score(), 10 + 100);
+    assert_eq!(s.count::<Enemy>(), 0);
+}
+
+#[test]
+fn diving_kill_scores_double() {
+    let mut s = Scenario::new().player_at(0.0).diving_enemy(EnemyType::Soldier, Vec2::new(0.0, 100.0));
+    s.fire().step(BULLET_FLIGHT);
+    assert_eq!(s.score(), 20);
+}
+
+#[test]
+fn a_kill_reaches_every_listener() { // Les points, les statistiques et les succès écoutent le même message.
+    let mut s = Scenario::new().player_at(0.0).diving_enemy(EnemyType::Soldier, Vec2::new(0.0, 100.0));
+    s.fire().step(BULLET_FLIGHT);
+    assert_eq!(s.score(), 20);
+    let stats = s.stats();
+    assert_eq!((stats.kills, stats.diving_kills, stats.bosses, stats.ships_lost), (1, 1, 0, 0));
+    assert_eq!(stats.waves_cleared, 1, "c'était le seul alien de la vague");
+    assert_eq!(s.achievements(), [Achievement::FirstBlood, Achievement::DiveBomber]);
+}
+
+#[test]
+fn kills_show_their_real_award_where_they_happen() {
+    let mut s = Scenario::new().player_at(0.0).diving_enemy(EnemyType::Soldier, Vec2::new(0.0, 100.0)).enemy(EnemyType::Soldier, Vec2::new(300.0, 300.0));
+    s.fire().step(BULLET_FLIGHT);
+    let popups = s.popups();
+    assert_eq!(popups.len(), 1);
+    assert_eq!(popups[0].0, "+20", "le double, en piqué");
+    let world = s.app.world_mut();
+    let y = world.query_filtered::<&Transform, With<FloatingScore>>().single(world).unwrap().translation.y;
+    assert!(y > 100.0 && y < 160.0, "il part de l'alien et monte : y = {}", y);
+    s.step(60);
+    assert!(s.popups().is_empty(), "puis il disparaît");
+}
+
+#[test]
+fn a_boss_kill_pops_up_bigger() {
+    let mut s = Scenario::new().player_at(0.0).enemy(EnemyType::Boss, Vec2::new(0.0, 100.0)).enemy(EnemyType::Soldier, Vec2::new(300.0, 300.0));
+    s.fire().step(BULLET_FLIGHT);
+    assert!(s.popups().is_empty(), "l'armure cassée ne fait pas de score volant");
+    s.fire().step(BULLET_FLIGHT);
+    let popups = s.popups();
+    assert_eq!(popups.len(), 1);
+    assert_eq!(popups[0].0, "+100");
+    assert!(popups[0].1 > 20.0, "plus gros que les +10 : {}", popups[0].1);
+}
+
+fn shot_at(kind: EnemyType, offset: f32) -> u32 { // Le score d'un tir qui passe à `offset` px du centre de l'alien.
+    let mut s = Scenario::new().player_at(0.0).enemy(kind, Vec2::new(offset, 100.0));
+    s.fire().step(BULLET_FLIGHT);
+    s.score()
+}
+
+#[test]
+fn boss_hitbox_follows_its_size() { // Un cercle de 31,25 px de rayon (2,5 fois un soldat), plus la demi-largeur de la balle.
+    assert_eq!(shot_at(EnemyType::Boss, 33.0), 10);
+    assert_eq!(shot_at(EnemyType::Boss, 35.0), 0);
+}
+
+#[test]
+fn soldier_hitbox_follows_its_size() { // Un cercle de 12,5 px de rayon, plus la demi-largeur de la balle.
+    assert_eq!(shot_at(EnemyType::Soldier, 14.0), 10);
+    assert_eq!(shot_at(EnemyType::Soldier, 16.0), 0);
+}
+
+#[test]
+fn one_enemy_bullet_hits_once() { // La balle disparaît au premier contact : pas de deuxième vie perdue au pas suivant.
+    let mut s = Scenario::new().player_at(0.0).enemy(EnemyType::Soldier, Vec2::new(300.0, 300.0)).enemy_bullet(Vec2::new(0.0, PLAYER_Y), Vec2::new(0.0, -10.0));
+    s.step(3);
+    assert_eq!(s.lives(), PLAYER_LIVES - 1);
+    assert_eq!(s.count::<Bullet>(), 0);
+}
+
+#[test]
+fn the_ship_is_drawn_even_without_its_image_file() { // Un dépôt tout neuf n'a pas de player_01.png : le jeu le dessine.
+    let mut s = Scenario::new().player_at(0.0).enemy(EnemyType::Soldier, Vec2::new(0.0, 300.0));
+    s.step(1);
+    assert!(s.player_image_size().is_some_and(|size| size.x > 0 && size.y > 0));
+}
+
+#[test]
+fn player_moves_and_stays_on_screen() {
+    let mut s = Scenario::new().player_at(0.0).enemy(EnemyType::Soldier, Vec2::new(0.0, 300.0));
+    s.hold(false, true).step(30); // Une demi-seconde à 500 px/s.
+    let x = s.player_x().expect("le joueur");
+    assert!((x - 250.0).abs() < 10.0, "x = {}", x);
+    s.step(600);
+    let limit = crate::playfield::DEFAULT_SIZE.x / 2.0 - Tuning::default().player_size.x / 2.0;
+    assert!(s.player_x().expect("le joueur") <= limit);
+}
+
+#[test]
+fn ramming_soldier_costs_a_life() {
+    let mut s = Scenario::new().player_at(0.0).enemy(EnemyType::Soldier, Vec2::new(0.0, PLAYER_Y));
+    s.step(1);
+    assert_eq!(s.lives(), PLAYER_LIVES - 1);
+    assert_eq!(s.count::<Player>(), 0, "le vaisseau explose");
+    assert_eq!(s.count::<Enemy>(), 0, "l'alien meurt dans le choc");
+    assert_eq!(s.score(), 0, "un choc ne rapporte rien");
+}
+
+#[test]
+fn enemy_bullet_costs_a_life() {
+    let mut s = Scenario::new().player_at(0.0).enemy_bullet(Vec2::new(0.0, PLAYER_Y + 60.0), Vec2::new(0.0, -300.0)); // Même sans aucun alien à l'écran.
+    s.step(15);
+    assert_eq!(s.lives(), PLAYER_LIVES - 1);
+    assert_eq!(s.count::<Bullet>(), 0);
+}
+
+#[test]
+fn several_hits_in_the_same_step_cost_one_life() {
+    let mut s = Scenario::new().player_at(0.0)
+        .enemy(EnemyType::Soldier, Vec2::new(0.0, PLAYER_Y))
+        .enemy_bullet(Vec2::new(-5.0, PLAYER_Y), Vec2::ZERO)
+        .enemy_bullet(Vec2::new(5.0, PLAYER_Y), Vec2::ZERO);
+    s.step(1);
+    assert_eq!(s.lives(), PLAYER_LIVES - 1);
+}
+
+#[test]
+fn passing_20000_then_70000_gives_extra_ships() {
+    let mut s = Scenario::new().player_at(0.0).with_score(19_995)
+        .enemy(EnemyType::Soldier, Vec2::new(0.0, 100.0))
+        .enemy(EnemyType::Soldier, Vec2::new(300.0, 300.0));
+    s.step(1);
+    assert_eq!(s.lives(), PLAYER_LIVES, "pas encore");
+    s.fire().step(BULLET_FLIGHT);
+    assert_eq!(s.lives(), PLAYER_LIVES + 1);
+    s.step(1);
+    assert_eq!(s.lives(), PLAYER_LIVES + 1, "un seul vaisseau par palier");
+    s = s.with_score(70_000);
+    s.step(1);
+    assert_eq!(s.lives(), PLAYER_LIVES + 2);
+}
+
+const NEXT_SHIP: u32 = 300; // L'explosion et le regroupement (2 s), puis le clignotement du vaisseau suivant (2,5 s).
+
+#[test]
+fn a_new_ship_arrives_blinking_and_untouchable() {
+    let mut s = Scenario::new().player_at(0.0).enemy(EnemyType::Soldier, Vec2::new(0.0, 300.0)).ram();
+    s.step(100);
+    assert_eq!(s.count::<Player>(), 0, "on laisse le temps de voir l'explosion");
+    s.step(30);
+    assert_eq!(s.count::<Player>(), 1);
+    assert_eq!(s.count::<Invulnerable>(), 1);
+    s = s.enemy_bullet(Vec2::new(0.0, PLAYER_Y), Vec2::ZERO);
+    s.step(1);
+    assert_eq!(s.lives(), PLAYER_LIVES - 1, "la balle le traverse");
+    s.step(NEXT_SHIP - 130);
+    assert_eq!(s.count::<Invulnerable>(), 0);
+    s.step(1);
+    assert_eq!(s.lives(), PLAYER_LIVES - 2, "la même balle, une fois le clignotement fini");
+}
+
+#[test]
+fn the_next_ship_waits_for_the_divers_to_regroup() {
+    let mut s = Scenario::new().player_at(0.0).diving_enemy(EnemyType::Soldier, Vec2::new(400.0, 200.0)).ram();
+    s.step(200);
+    assert_eq!(s.count::<Player>(), 0, "un alien est encore en piqué");
+    s.regroup().step(1);
+    assert_eq!(s.count::<Player>(), 1);
+}
+
+fn lose_every_ship(mut s: Scenario) -> Scenario { // Le vaisseau se fait percuter jusqu'au dernier, et on attend "GAME OVER".
+    while s.lives() > 1 { // Un bonus "vie" ramassé en route en ajoute une.
+        s = s.ram();
+        s.step(NEXT_SHIP);
+        for _ in 0..60 { // Un alien en piqué peut encore retarder le vaisseau suivant.
+            if s.count::<Player>() == 1 && s.count::<Invulnerable>() == 0 { break; }
+            s.step(10);
+        }
+    }
+    s = s.ram();
+    s.step(1);
+    assert_eq!(s.screen(), AppState::Playing, "l'explosion d'abord");
+    s.step(NEXT_SHIP / 2);
+    s
+}
+
+#[test]
+fn last_enemy_down_moves_to_the_next_wave() {
+    let mut s = Scenario::new().player_at(0.0).enemy(EnemyType::Soldier, Vec2::new(0.0, 100.0));
+    s.fire().step(BULLET_FLIGHT);
+    assert_eq!(s.wave_state(), WaveState::Waiting);
+    s.step(200); // La pause entre deux vagues (2 s), puis les premiers aliens arrivent.
+    assert_eq!(s.wave_state(), WaveState::Spawning);
+    assert!(s.count::<Enemy>() > 0);
+}
+
+#[test]
+fn losing_the_last_life_is_game_over() {
+    let s = lose_every_ship(Scenario::new().player_at(0.0).enemy(EnemyType::Soldier, Vec2::new(0.0, 300.0)));
+    assert_eq!(s.screen(), AppState::GameOver);
+    assert_eq!(s.lives(), 0);
+}
+
+#[test]
+fn game_over_leads_back_to_the_title_with_the_field_cleared() {
+    let mut s = lose_every_ship(Scenario::new().player_at(0.0)
+        .enemy(EnemyType::Boss, Vec2::new(300.0, 200.0))
+        .power_up(BonusType::ExtraLife, Vec2::new(-300.0, 0.0)));
+    assert_eq!(s.screen(), AppState::GameOver);
+    assert_eq!(s.count::<Enemy>(), 1, "le terrain reste figé sous GAME OVER");
+    s.step(NEXT_SHIP / 2); // Le temps de lire "GAME OVER".
+    assert_eq!(s.screen(), AppState::HighScoreEntry);
+    assert_eq!(s.count::<Enemy>(), 0);
+    assert_eq!(s.count::<PowerUp>(), 0, "les bonus aussi disparaissent");
+    assert_eq!(s.count::<DespawnOnExit<InGame>>(), 0);
+    s.fire().step(1);
+    assert_eq!(s.screen(), AppState::Title);
+    s.fire().step(1);
+    assert_eq!(s.screen(), AppState::Playing);
+    assert_eq!(s.lives(), PLAYER_LIVES, "tous les vaisseaux sont revenus");
+    assert_eq!(s.count::<Player>(), 1);
+    assert_eq!(s.score(), 0);
+}
+
+#[test]
+fn a_scoring_game_asks_for_initials_and_enters_the_table() {
+    let mut s = Scenario::new().player_at(0.0).enemy(EnemyType::Soldier, Vec2::new(0.0, 100.0)).enemy(EnemyType::Soldier, Vec2::new(300.0, 300.0));
+    s.fire().step(BULLET_FLIGHT);
+    s = lose_every_ship(s);
+    s.step(NEXT_SHIP / 2);
+    assert_eq!(s.screen(), AppState::HighScoreEntry);
+    s.hold(false, true).step(3); // Un seul appui, même tenu plusieurs pas : A -> B.
+    s.hold(false, false).fire().step(1);
+    s.fire().step(1);
+    s.hold(true, false).step(1); // A -> "." (on fait le tour de l'alphabet).
+    s.hold(false, false).fire().step(1);
+    assert_eq!(s.screen(), AppState::HighScoreEntry, "le tableau s'affiche avant de revenir au titre");
+    let best = &s.high_scores().entries[0];
+    assert_eq!((best.initials.as_str(), best.score, best.level, best.wave), ("BA.", 10, 1, 1));
+    s.fire().step(1);
+    assert_eq!(s.screen(), AppState::Title);
+}
+
+#[test]
+fn pause_freezes_the_game() {
+    let mut s = Scenario::new().player_at(0.0).enemy(EnemyType::Soldier, Vec2::new(0.0, 100.0));
+    s.pause().step(1);
+    assert_eq!(s.screen(), AppState::Paused);
+    s.fire().step(BULLET_FLIGHT);
+    assert_eq!(s.count::<Bullet>(), 0, "on ne tire pas en pause");
+    s.pause().step(1);
+    assert_eq!(s.screen(), AppState::Playing);
+    s.fire().step(BULLET_FLIGHT);
+    assert_eq!(s.score(), 10);
+}
+
+#[test]
+fn pause_freezes_the_animations() { // La flamme du réacteur vacille en jeu, et se fige en pause.
+    let mut s = Scenario::new().player_at(0.0).enemy(EnemyType::Soldier, Vec2::new(0.0, 300.0));
+    let mut seen = Vec::new();
+    for _ in 0..12 { s.step(1); seen.push(s.player_frame().expect("le vaisseau est une planche")); }
+    assert!(seen.contains(&0) && seen.contains(&1), "{:?}", seen);
+    s.pause().step(1);
+    let frozen = s.player_frame();
+    for _ in 0..12 { s.step(1); assert_eq!(s.player_frame(), frozen); }
+}
+
+#[test]
+fn the_stars_rush_between_levels_and_freeze_in_pause() { // Le saut vers le niveau suivant, et le ciel figé de la pause.
+    let mut s = Scenario::new().player_at(0.0).enemy(EnemyType::Soldier, Vec2::new(0.0, 300.0));
+    let cruising = s.star_drift(10);
+    assert!(cruising > 0.0);
+    s.app.world_mut().resource_mut::<WaveManager>().state = WaveState::LevelCompleted;
+    s.step(40);
+    let warping = s.star_drift(10);
+    assert!(warping > 4.0 * cruising, "{} puis {}", cruising, warping);
+    s.pause().step(1);
+    assert_eq!(s.star_drift(30), 0.0);
+}
+
+#[test]
+fn shorter_level_files_do_not_freeze_the_game() { // On reprend à la dernière vague qui existe encore, et la partie continue.
+    let mut s = Scenario::new().player_at(0.0).at_wave(1, 4).enemy(EnemyType::Soldier, Vec2::new(0.0, 100.0));
+    s.shorten_campaign(3, 2).step(1);
+    assert_eq!(s.progress(), (1, 2));
+    s.fire().step(BULLET_FLIGHT);
+    assert_eq!(s.score(), 10);
+    s.step(120);
+    assert_eq!(s.progress(), (2, 1), "la dernière vague finie, on passe au niveau suivant");
+
+    let mut s = Scenario::new().player_at(0.0).at_wave(3, 2).enemy(EnemyType::Soldier, Vec2::new(0.0, 100.0));
+    s.shorten_campaign(1, 5).step(1);
+    assert_eq!(s.progress(), (1, 1));
+    assert_eq!(s.wave_state(), WaveState::Fighting);
+}
+
+#[test]
+fn r_does_not_abort_a_replay() { // Seul le replay pilote : la touche R ne ramène pas au titre.
+    let mut s = Scenario::new().player_at(0.0).enemy(EnemyType::Soldier, Vec2::new(0.0, 300.0));
+    s.app.world_mut().write_message(KeyboardInput { key_code: KeyCode::KeyR, logical_key: Key::Character("r".into()), state: ButtonState::Pressed, text: None, repeat: false, window: Entity::PLACEHOLDER });
+    s.step(2);
+    assert_eq!(s.screen(), AppState::Playing);
+}
+
+const TRACTOR_PARK: f32 = 230.0; // Le chef se gare à cette hauteur au-dessus du joueur (voir tractor.rs).
+
+fn captured_ship() -> Scenario { // Un chef qui vient d'aspirer le vaisseau.
+    let mut s = Scenario::new().player_at(0.0).tractor_boss(Vec2::new(0.0, PLAYER_Y + TRACTOR_PARK));
+    assert!(s.step_until(240, |s| s.count::<CapturedFighter>() == 1), "le rayon aspire le vaisseau");
+    s
+}
+
+#[test]
+fn the_beam_captures_the_ship_and_the_captive_follows_its_boss() {
+    let mut s = captured_ship();
+    assert_eq!(s.count::<Player>(), 0);
+    assert_eq!(s.lives(), PLAYER_LIVES - 1, "un vaisseau de perdu");
+    s.step(60); // Le rayon s'éteint et le chef rentre à sa place, son prisonnier au-dessus de lui.
+    let (boss, captive) = (s.positions::<Enemy>()[0], s.positions::<CapturedFighter>()[0]);
+    assert!(boss.y > PLAYER_Y + TRACTOR_PARK, "le chef remonte : {}", boss);
+    assert_eq!(captive, boss + Vec2::new(0.0, 40.0));
+}
+
+#[test]
+fn killing_the_boss_rescues_a_dual_fighter_that_fires_twice() {
+    let mut s = captured_ship();
+    assert!(s.step_until(600, |s| s.count::<Player>() == 1), "un nouveau vaisseau arrive");
+    assert!(s.step_until(300, |s| s.count::<Invulnerable>() == 0));
+    let boss_x = s.positions::<Enemy>()[0].x;
+    let player_x = s.player_x().expect("le nouveau vaisseau");
+    s.hold(boss_x < player_x, boss_x > player_x);
+    assert!(s.step_until(120, |s| (s.player_x().unwrap() - boss_x).abs() < 5.0), "on se place sous le chef");
+    s.hold(false, false);
+    for _ in 0..2 { s.fire().step(BULLET_FLIGHT); } // Un chef encaisse un tir avant de tomber.
+    assert_eq!(s.count::<Enemy>(), 0);
+    assert_eq!(s.count::<CapturedFighter>(), 0);
+    assert_eq!(s.count::<RescuedFighter>(), 1, "le prisonnier est libre");
+    assert!(s.step_until(300, |s| s.count::<DualFighter>() == 1), "il vient se coller au vaisseau");
+    s.step(30);
+    s.fire().step(1);
+    assert_eq!(s.count::<Bullet>(), 2, "deux vaisseaux, deux tirs");
+}
+
+#[test]
+fn shooting_the_captive_destroys_it_but_spares_the_boss() {
+    let mut s = captured_ship();
+    let captive = s.positions::<CapturedFighter>()[0];
+    s.player_bullet_at(captive).step(1);
+    assert_eq!(s.count::<CapturedFighter>(), 0);
+    assert_eq!(s.count::<Bullet>(), 0);
+    assert!(s.boss_untouched(), "le chef n'a rien senti");
+}
+
+#[test]
+fn one_bullet_hits_the_captive_or_its_boss_not_both() { // Une balle assez longue pour toucher les deux à la fois.
+    let mut s = captured_ship();
+    s.step(60); // Le prisonnier a rejoint sa place au-dessus du chef.
+    let boss = s.positions::<Enemy>()[0];
+    s.player_bullet_at(boss + Vec2::new(0.0, 32.0)).step(1); // Le haut du chef et le bas du prisonnier.
+    let captive_hit = s.count::<CapturedFighter>() == 0;
+    let boss_hit = !s.boss_untouched();
+    assert!(captive_hit != boss_hit, "prisonnier touché : {}, chef touché : {}", captive_hit, boss_hit);
+    assert_eq!(s.count::<Bullet>(), 0);
+}
+
+#[test]
+fn a_challenging_stage_counts_the_hits_and_pays_the_bonus() { // On abat chaque soldat acrobate, jamais les chefs : 36 touches sur 40.
+    let mut s = Scenario::new().player_at(0.0).challenging_stage();
+    let mut states = vec![s.wave_state()];
+    let (mut hits, mut score_before_bonus) = (0, 0);
+    for _ in 0..4000 {
+        for (kind, at) in s.flyers() {
+            if kind == EnemyType::Soldier && at.y.abs() < 250.0 { s.player_bullet_at(at); } // Une balle sur chaque soldat à l'écran.
+        }
+        let score = s.score();
+        s.step(1);
+        assert_eq!(s.enemy_bullets(), 0, "les acrobates ne tirent jamais");
+        if s.wave_state() == *states.last().unwrap() { continue; }
+        states.push(s.wave_state());
+        if s.wave_state() == WaveState::ChallengeResults { (hits, score_before_bonus) = (s.app.world().resource::<WaveManager>().enemies_killed_by_player, score); }
+        if s.wave_state() == WaveState::Spawning { break; } // Le stage suivant commence.
+    }
+    assert_eq!(states, [WaveState::Spawning, WaveState::Fighting, WaveState::ChallengeResults, WaveState::Waiting, WaveState::Spawning]);
+    assert_eq!(hits, 36, "NUMBER OF HITS");
+    assert_eq!(challenge::results_message(hits), "NUMBER OF HITS  36\nBONUS  3600");
+    assert_eq!(s.score(), 36 * damage::score_for_hit(&EnemyType::Soldier, HitOutcome::Destroyed, false) + challenge::bonus(36));
+    assert!(s.score() - score_before_bonus >= challenge::bonus(36), "le bonus tombe à la fin du stage");
+    assert_eq!(s.lives(), PLAYER_LIVES, "ils ne foncent pas sur le vaisseau");
+    assert_eq!(s.app.world().resource::<WaveManager>().stage_kind, StageKind::Normal, "le stage 4 est normal");
+}
+
+fn play(replay: Replay, ticks: u32, pilot: impl Fn(u32) -> PlayerInput) -> App { // Le jeu entier, sans scénario : piloté pas à pas, ou par le replay.
+    let mut app = build_app(true, SEED, replay);
+    assert!(headless::wait_until_loaded(&mut app), "les fichiers du jeu ne se chargent pas");
+    for tick in 0..ticks {
+        if !app.world().resource::<Replay>().is_playback() { *app.world_mut().resource_mut::<PlayerInput>() = pilot(tick); }
+        app.update();
+    }
+    app
+}
+
+fn outcome(app: &App) -> (AppState, u32, u32, u32, WaveState, i32) { // Où en est la partie : écran, score, niveau, vague, état de la vague, vies.
+    let (world, wave_mgr) = (app.world(), app.world().resource::<WaveManager>());
+    (*world.resource::<State<AppState>>().get(), world.resource::<GameState>().score, wave_mgr.current_level, wave_mgr.current_wave, wave_mgr.state, world.resource::<Lives>().ships)
+}
+
+#[test]
+fn a_recorded_game_plays_back_the_same() { // On enregistre une partie, on écrit et relit le fichier, on la rejoue : même fin.
+    let pilot = |tick: u32| PlayerInput { left: tick % 240 < 60, right: (120..180).contains(&(tick % 240)), fire: tick.is_multiple_of(8), pause: false };
+    let recording = Recording { seed: SEED, tuning: None, frames: Vec::new() };
+    let recorded = play(Replay::Recording { path: PathBuf::from("inutilisé.xgr"), recording }, 600, pilot);
+    let Replay::Recording { recording, .. } = recorded.world().resource::<Replay>() else { unreachable!() };
+    assert_eq!(recording.tuning.as_ref(), Some(recorded.world().resource::<Tuning>()), "les réglages de la partie sont dans l'en-tête");
+    let file = Recording::decode(&recording.encode()).expect("le replay se relit");
+    assert_eq!(&file, recording);
+
+    let replayed = play(Replay::Playback { recording: file, cursor: 0 }, 600, |_| PlayerInput::default());
+    let (screen, score, ..) = outcome(&recorded);
+    assert_eq!(screen, AppState::Playing, "toujours la même partie");
+    assert!(score > 0, "le pilote a abattu des aliens");
+    assert_eq!(outcome(&replayed), outcome(&recorded));
+}
+
+fn edit_tuning_file(app: &mut App, edit: impl Fn(&mut Tuning)) { // Comme si on enregistrait le fichier de réglages pendant que le jeu tourne.
+    for (_, tuning) in app.world_mut().resource_mut::<Assets<Tuning>>().iter_mut() { edit(tuning); }
+    app.update(); // L'événement de l'asset part...
+    app.update(); // ... et les réglages le lisent.
+}
+
+#[test]
+fn the_tuning_reloads_between_games_while_recording() { // Seule la partie en cours garde les réglages notés dans le replay.
+    let mut app = build_app(true, SEED, Replay::Recording { path: PathBuf::from("inutilisé.xgr"), recording: Recording { seed: SEED, tuning: None, frames: Vec::new() } });
+    app.insert_resource(FirstScreen(AppState::Title));
+    assert!(headless::wait_until_loaded(&mut app), "les fichiers du jeu ne se chargent pas");
+    assert_eq!(*app.world().resource::<State<AppState>>().get(), AppState::Title);
+    edit_tuning_file(&mut app, |tuning| tuning.player_speed = 123.0);
+    assert_eq!(app.world().resource::<Tuning>().player_speed, 123.0, "sur l'écran titre, le rechargement passe");
+
+    app.world_mut().resource_mut::<NextState<AppState>>().set(AppState::Playing);
+    app.update();
+    edit_tuning_file(&mut app, |tuning| tuning.player_speed = 456.0);
+    assert_eq!(app.world().resource::<Tuning>().player_speed, 123.0, "pendant la partie, on garde les réglages du replay");
+    let Replay::Recording { recording, .. } = app.world().resource::<Replay>() else { unreachable!() };
+    assert_eq!(recording.tuning.as_ref().map(|tuning| tuning.player_speed), Some(123.0));
+}
+
+#[test]
+fn new_speeds_reach_the_shots_already_flying() { // Pas seulement les prochains tirs.
+    let mut s = Scenario::new().player_at(0.0).enemy_bullet(Vec2::new(0.0, 200.0), Vec2::new(30.0, -216.0));
+    s.fire().step(1);
+    s.edit_tuning(|tuning| { tuning.bullet_speed = 1400.0; tuning.enemy_speed = 60.0; });
+    let mut velocities = s.bullet_velocities();
+    velocities.sort_by_key(|(from_player, _)| *from_player);
+    assert_eq!(velocities, [(false, Vec2::new(15.0, -108.0)), (true, Vec2::new(0.0, 1400.0))]);
+}
+
+#[test]
+fn a_replay_plays_with_its_own_tuning() { // Même si le fichier de réglages a changé depuis l'enregistrement.
+    let tuning = Tuning { player_speed: 123.0, ..default() };
+    let app = play(Replay::Playback { recording: Recording { seed: SEED, tuning: Some(tuning.clone()), frames: Vec::new() }, cursor: 0 }, 10, |_| PlayerInput::default());
+    assert_eq!(app.world().resource::<Tuning>(), &tuning);
+}
@@ -0,0 +1,141 @@
+// ═══════════════════════════════════════════════════════════════════════════
+// 🚦 LES ÉCRANS : titre, partie, pause, fin de partie, victoire, scores
+// ═══════════════════════════════════════════════════════════════════════════
+//
+// Une partie suit toujours le même chemin :
+//
+//   Chargement ──> Titre ──Espace──> Partie <──P──> Pause
+//                                    │
+//                                    ├── plus de vies ──> Game Over ──┐
+//                                    └── dernier niveau ─> Victoire ──┴──> Scores ──Espace──> Titre
+//
+// Le chargement attend les niveaux, les réglages et les images : rien ne bouge
+// avant. Sans fenêtre ou en replay, il mène directement à la partie.
+//
+// Sur l'écran des scores, un score qui entre dans le tableau demande d'abord
+// les initiales du joueur (voir highscores.rs).
+//
+// Tout ce qui appartient à une partie (vaisseau, aliens, balles, bonus,
+// explosions, prisonniers...) porte `DespawnOnExit(InGame)` : quand on quitte
+// la partie pour le tableau des scores, Bevy le fait disparaître tout seul, même
+// ce qu'on aurait oublié de lister. Les textes d'un écran disparaissent de la
+// même façon quand on quitte cet écran.
+//
+// Les écrans avancent avec les commandes du joueur (`PlayerInput`), à pas fixe,
+// comme la partie. Un replay ne contient qu'une partie, depuis son premier pas
+// (l'enregistrement repart de zéro à chaque partie) : il commence donc
+// directement sur la partie, sans passer par le titre, et R ne l'interrompt pas.
+
+use bevy::prelude::*;
+
+use crate::highscores::{self, HighScore, HighScores, InitialsEntry};
+use crate::input::PlayerInput;
+use crate::tuning::StatusMessage;
+use crate::{AppState, GameState, WaveManager};
+
+const END_SCREEN_DURATION: f32 = 3.0; // Le temps pendant lequel on lit "GAME OVER" ou "VICTOIRE" sur le terrain figé.
+const SCORES_SCREEN_DURATION: f32 = 10.0; // Sans appui, le tableau des scores repart tout seul vers le titre.
+const INITIALS_TIMEOUT: f32 = 30.0; // Sans réponse, les initiales en cours sont validées toutes seules.
+
+#[derive(Component)] pub struct ScoresText; // Le texte qui change sur l'écran des scores (initiales, tableau).
+
+#[derive(Resource, Default)]
+pub struct ScreenTimer(Timer); // Le chrono de l'écran en cours (fin de partie, scores).
+
+#[derive(Resource)]
+pub struct FirstScreen(pub AppState); // L'écran qui suit le chargement.
+
+pub fn loading_done_system(first: Res<FirstScreen>, mut next_state: ResMut<NextState<AppState>>) { next_state.set(first.0); } // Les fichiers sont là : on commence.
+
+pub fn apply_state_system(world: &mut World) { // Un changement d'écran (ou la pause) prend effet dès ce pas, pas seulement à la prochaine image.
+    world.run_schedule(StateTransition);
+}
+
+fn full_screen_column() -> Node { // Une colonne centrée qui prend tout l'écran.
+    Node {
+        position_type: PositionType::Absolute,
+        width: Val::Percent(100.0),
+        height: Val::Percent(100.0),
+        flex_direction: FlexDirection::Column,
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        row_gap: Val::Px(20.0),
+        ..default()
+    }
+}
+
+pub fn spawn_title_screen(mut commands: Commands) { // Le nom du jeu et les touches.
+    commands.spawn((DespawnOnExit(AppState::Title), full_screen_column())).with_children(|parent| {
+        parent.spawn((Text::new("XGALAGA"), TextFont::from_font_size(80.0), TextColor(Color::srgb(1.0, 0.9, 0.3))));
+        parent.spawn((Text::new("ESPACE pour jouer"), TextFont::from_font_size(30.0)));
+        parent.spawn((Text::new("Flèches : bouger   Espace : tirer   P : pause   R : retour au titre   Q : quitter"), TextFont::from_font_size(16.0)));
+    });
+}
+
+pub fn title_system(input: Res<PlayerInput>, mut next_state: ResMut<NextState<AppState>>) { // Espace lance la partie.
+    if input.fire { next_state.set(AppState::Playing); }
+}
+
+pub fn start_end_screen(mut timer: ResMut<ScreenTimer>) { // "GAME OVER" ou "VICTOIRE" : on laisse le temps de lire.
+    timer.0 = Timer::from_seconds(END_SCREEN_DURATION, TimerMode::Once);
+}
+
+pub fn end_screen_system(time: Res<Time>, mut timer: ResMut<ScreenTimer>, mut next_state: ResMut<NextState<AppState>>) { // Puis on passe au tableau des scores.
+    timer.0.tick(time.delta());
+    if timer.0.is_finished() { next_state.set(AppState::HighScoreEntry); }
+}
+
+pub fn spawn_scores_screen( // Le bilan de la partie, puis les initiales si le score entre dans le tableau.
+    mut commands: Commands,
+    mut timer: ResMut<ScreenTimer>,
+    mut entry: ResMut<InitialsEntry>,
+    scores: Res<HighScores>,
+    state: Res<GameState>,
+    wave_mgr: Res<WaveManager>,
+) {
+    entry.begin(scores.qualifies(state.score));
+    let duration = if entry.active { INITIALS_TIMEOUT } else { SCORES_SCREEN_DURATION };
+    timer.0 = Timer::from_seconds(duration, TimerMode::Once);
+    commands.spawn((DespawnOnExit(AppState::HighScoreEntry), full_screen_column())).with_children(|parent| {
+        parent.spawn((Text::new("SCORE FINAL"), TextFont::from_font_size(40.0)));
+        parent.spawn((Text::new(state.score.to_string()), TextFont::from_font_size(60.0), TextColor(Color::srgb(1.0, 0.9, 0.3))));
+        parent.spawn((Text::new(format!("Niveau {}  Vague {}", wave_mgr.current_level, wave_mgr.current_wave)), TextFont::from_font_size(20.0)));
+        parent.spawn((ScoresText, Text::new(""), TextFont::from_font_size(20.0), TextLayout::new_with_justify(Justify::Center)));
+    });
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn scores_screen_system( // Les initiales, puis Espace (ou l'attente) ramène au titre.
+    time: Res<Time>,
+    input: Res<PlayerInput>,
+    mut timer: ResMut<ScreenTimer>,
+    mut entry: ResMut<InitialsEntry>,
+    mut scores: ResMut<HighScores>,
+    mut status: ResMut<StatusMessage>,
+    state: Res<GameState>,
+    wave_mgr: Res<WaveManager>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    timer.0.tick(time.delta());
+    if entry.active {
+        if !entry.update(&input) && !timer.0.is_finished() { return; }
+        entry.active = false; // La dernière lettre est validée (ou le temps est écoulé) : on range la partie.
+        entry.rank = Some(scores.insert(HighScore {
+            initials: entry.initials(), score: state.score, level: wave_mgr.current_level, wave: wave_mgr.current_wave, date: highscores::today(),
+        }));
+        if let Err(err) = scores.save() { status.show(format!("Impossible d'enregistrer les scores : {}", err)); }
+        timer.0 = Timer::from_seconds(SCORES_SCREEN_DURATION, TimerMode::Once);
+        return;
+    }
+    if input.fire || timer.0.is_finished() { next_state.set(AppState::Title); }
+}
+
+pub fn scores_text_system(entry: Res<InitialsEntry>, scores: Res<HighScores>, mut text_q: Query<&mut Text, With<ScoresText>>) { // On affiche ce qui se passe sur l'écran des scores.
+    let Ok(mut text) = text_q.single_mut() else { return };
+    let shown = if entry.active {
+        format!("NOUVEAU RECORD !\nENTREZ VOS INITIALES\n\n{}\n\nFlèches : lettre   Espace : valider", entry.display())
+    } else {
+        format!("MEILLEURS SCORES\n\n{}\n\nESPACE pour continuer", highscores::table_text(&scores, entry.rank))
+    };
+    if text.0 != shown { text.0 = shown; }
+}
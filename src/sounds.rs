@@ -0,0 +1,39 @@
+// ═══════════════════════════════════════════════════════════════════════════
+// 🔊 LES SONS : de petits bips fabriqués par le jeu, en attendant de vrais bruitages
+// ═══════════════════════════════════════════════════════════════════════════
+//
+// Il n'y a pas encore de fichiers de sons dans assets/. Bevy sait jouer une
+// note pure (`Pitch`) : chaque message du jeu a donc sa petite note. Sans
+// fenêtre, il n'y a pas de son du tout, et ce système ne tourne pas.
+
+use std::time::Duration;
+
+use bevy::audio::Pitch;
+use bevy::prelude::*;
+
+use crate::messages::{EnemyDamaged, EnemyKilled, PlayerHit, PowerUpCollected, WaveCleared};
+use crate::EnemyType;
+
+pub fn beep(commands: &mut Commands, pitches: &mut Assets<Pitch>, frequency: f32, seconds: f32) { // Une note, qui s'efface toute seule quand elle est finie.
+    let note = pitches.add(Pitch::new(frequency, Duration::from_secs_f32(seconds)));
+    commands.spawn((AudioPlayer(note), PlaybackSettings::DESPAWN));
+}
+
+pub fn sound_effects_system( // Chaque message du jeu a sa note.
+    mut commands: Commands,
+    mut pitches: ResMut<Assets<Pitch>>,
+    mut damaged: MessageReader<EnemyDamaged>,
+    mut killed: MessageReader<EnemyKilled>,
+    mut player_hit: MessageReader<PlayerHit>,
+    mut collected: MessageReader<PowerUpCollected>,
+    mut cleared: MessageReader<WaveCleared>,
+) {
+    if damaged.read().count() > 0 { beep(&mut commands, &mut pitches, 880.0, 0.05); } // Un "tic" sur l'armure.
+    for kill in killed.read() {
+        let frequency = match kill.kind { EnemyType::Soldier => 440.0, EnemyType::Boss => 220.0 }; // Le chef tombe plus grave.
+        beep(&mut commands, &mut pitches, frequency, 0.08);
+    }
+    if player_hit.read().count() > 0 { beep(&mut commands, &mut pitches, 110.0, 0.4); } // Le grondement de l'explosion du vaisseau.
+    if collected.read().count() > 0 { beep(&mut commands, &mut pitches, 1318.5, 0.1); }
+    if cleared.read().count() > 0 { beep(&mut commands, &mut pitches, 659.25, 0.2); }
+}
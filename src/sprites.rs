@@ -0,0 +1,552 @@
+// ═══════════════════════════════════════════════════════════════════════════
+// 🎨 LES IMAGES DU JEU : des packs d'images, et des dessins en dernier recours
+// ═══════════════════════════════════════════════════════════════════════════
+//
+// Le jeu ne connaît plus de noms de fichiers : il demande une image par son
+// rôle (`SpriteId` : le vaisseau, le soldat venu de gauche, le chef, la balle
+// du joueur...). Un pack d'images est un dossier de assets/sprites/ avec un
+// fichier `pack.sprites.ron` qui dit quel fichier joue quel rôle, et dans
+// quel autre pack chercher ce qui lui manque :
+//
+//   xgalaga ──> classic ──> Reserve-Sprites ──> le dessin du jeu
+//
+// Chaque chaîne finit par assets/sprites/Reserve-Sprites. Si aucun pack n'a
+// l'image (ou si le fichier est illisible), le jeu la dessine en petits
+// pixels, comme les aliens dessinés de la version macroquad : un vaisseau en
+// triangle avec son cockpit, des aliens ronds aux yeux rouges et aux antennes
+// jaunes. Un dépôt tout neuf, sans aucune image, est donc jouable.
+//
+// Une image peut aussi être une planche qui s'anime (voir animation.rs) :
+// décrite à la main dans le manifeste, ou exportée par Aseprite (un fichier
+// `.json` à la place de l'image, voir aseprite.rs).
+//
+// Le pack se choisit avec `sprite_pack` dans les réglages ; en changer pendant
+// la partie rhabille tout de suite ce qui est à l'écran (voir `Skin`).
+
+use std::collections::{HashMap, HashSet};
+
+use bevy::asset::io::{AssetReaderError, Reader};
+use bevy::asset::{AssetLoadError, AssetLoadFailedEvent, AssetLoader, LoadContext, LoadDirectError, RecursiveDependencyLoadState, RenderAssetUsages};
+use bevy::image::ImageSampler;
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use serde::Deserialize;
+
+use crate::animation::AnimationClip;
+use crate::aseprite::AsepriteSheet;
+use crate::levels::{self, LevelLoadError};
+use crate::tuning::{StatusMessage, Tuning};
+
+pub const RESERVE_PACK: &str = "Reserve-Sprites"; // Le dernier pack de toutes les chaînes.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize)]
+pub enum SpriteId { // Les rôles qu'une image peut jouer.
+    Player, // Le vaisseau du joueur (et ses vaisseaux de réserve).
+    SoldierLeft, // Le soldat des vagues qui arrivent par la gauche.
+    SoldierRight, // ... par la droite.
+    SoldierTop, // ... par le haut.
+    Boss, // Le chef.
+    PlayerBullet,
+    EnemyBullet,
+    Explosion,
+}
+
+pub const ALL_SPRITES: [SpriteId; 8] = [SpriteId::Player, SpriteId::SoldierLeft, SpriteId::SoldierRight, SpriteId::SoldierTop, SpriteId::Boss, SpriteId::PlayerBullet, SpriteId::EnemyBullet, SpriteId::Explosion];
+
+#[derive(Component, Clone, Copy, Debug)]
+pub struct Skin(pub SpriteId); // Le rôle de l'image d'un objet : quand le pack change, son image change aussi.
+
+type Rgba = [u8; 4];
+const CLEAR: Rgba = [0, 0, 0, 0]; // Un pixel transparent.
+
+const SHIP: [&str; 8] = [ // Le triangle de la version macroquad, avec son cockpit, ses canons et son réacteur.
+    ".......W.......",
+    "......WBW......",
+    "..R...WBW...R..",
+    "..W..WWBWW..W..",
+    "R.W.WWWWWWW.W.R",
+    "WWWWWWRWRWWWWWW",
+    "WWW.WWWWWWW.WWW",
+    "W....WWFWW....W",
+];
+const SHIP_COLORS: [(char, Rgba); 4] = [('W', [102, 191, 255, 255]), ('B', [0, 121, 241, 255]), ('R', [230, 41, 55, 255]), ('F', [253, 249, 0, 255])];
+const SHIP_THRUST_COLORS: [(char, Rgba); 4] = [('W', [102, 191, 255, 255]), ('B', [0, 121, 241, 255]), ('R', [230, 41, 55, 255]), ('F', [255, 120, 0, 255])]; // La flamme vacille du jaune à l'orange.
+
+const ALIEN: [&str; 13] = [ // Deux cercles, deux yeux et deux antennes, comme dans la version macroquad.
+    "..Y.......Y..",
+    "...A.....A...",
+    "....A...A....",
+    "....DDDDD....",
+    "..DDBBBBBDD..",
+    ".DBBBBBBBBBD.",
+    ".DBEEBBBEEBD.",
+    "DBBEEBBBEEBBD",
+    "DBBBBBBBBBBBD",
+    ".DBBBBBBBBBD.",
+    ".DDBBB.BBBDD.",
+    "..DD.....DD..",
+    "...D.....D...",
+];
+const ALIEN_FLAP: [&str; 13] = [ // Le même, pattes écartées : les deux vignettes du battement d'ailes.
+    ".Y.........Y.",
+    "..A.......A..",
+    "...A.....A...",
+    "....DDDDD....",
+    "..DDBBBBBDD..",
+    ".DBBBBBBBBBD.",
+    ".DBEEBBBEEBD.",
+    "DBBEEBBBEEBBD",
+    "DBBBBBBBBBBBD",
+    ".DBBBBBBBBBD.",
+    ".DDBBB.BBBDD.",
+    "DD.........DD",
+    "D...........D",
+];
+const CRACKS: [(usize, usize); 7] = [(4, 5), (5, 4), (5, 5), (8, 6), (8, 7), (9, 7), (9, 8)]; // Les trous du chef abîmé (ligne, colonne).
+const ALIEN_GREEN_COLORS: [(char, Rgba); 5] = [('D', [0, 117, 44, 255]), ('B', [0, 228, 48, 255]), ('A', [0, 228, 48, 255]), ('E', [230, 41, 55, 255]), ('Y', [253, 249, 0, 255])];
+const ALIEN_RED_COLORS: [(char, Rgba); 5] = [('D', [160, 40, 40, 255]), ('B', [255, 120, 120, 255]), ('A', [255, 120, 120, 255]), ('E', [253, 249, 0, 255]), ('Y', [253, 249, 0, 255])]; // Clair : le chef le teinte en vert ou en bleu.
+const ALIEN_GREY_COLORS: [(char, Rgba); 5] = [('D', [90, 90, 100, 255]), ('B', [200, 200, 210, 255]), ('A', [200, 200, 210, 255]), ('E', [230, 41, 55, 255]), ('Y', [253, 249, 0, 255])];
+
+const BULLET: [&str; 9] = [ // Une balle trois fois plus haute que large, comme `bullet_size`.
+    ".W.",
+    "WCW",
+    "WCW",
+    "WCW",
+    "WCW",
+    "WCW",
+    ".W.",
+    ".T.",
+    ".T.",
+];
+const PLAYER_BULLET_COLORS: [(char, Rgba); 3] = [('W', [253, 249, 0, 255]), ('C', [255, 255, 255, 255]), ('T', [255, 161, 0, 160])];
+const ENEMY_BULLET_COLORS: [(char, Rgba); 3] = [('W', [220, 220, 220, 255]), ('C', [255, 255, 255, 255]), ('T', [180, 180, 180, 160])]; // Blanche : le jeu la teinte en rouge.
+
+const EXPLOSION_SIZE: u32 = 16; // L'explosion est calculée pixel par pixel, pas dessinée à la main.
+const EXPLOSION_FRAMES: usize = 6;
+
+struct Drawing { image: Image, frame_size: UVec2, frames: u32 } // Un dessin du jeu : une vignette, ou plusieurs côte à côte.
+
+fn drawing(id: SpriteId) -> Drawing { // Le dessin du jeu, quand aucun pack n'a l'image (rangé comme dans Reserve-Sprites).
+    let alien = |colors: &[(char, Rgba)]| vec![pixels(&ALIEN, colors, &[]), pixels(&ALIEN_FLAP, colors, &[])];
+    let (size, frames) = match id {
+        SpriteId::Player => (UVec2::new(15, 8), vec![pixels(&SHIP, &SHIP_COLORS, &[]), pixels(&SHIP, &SHIP_THRUST_COLORS, &[])]),
+        SpriteId::SoldierLeft => (UVec2::splat(13), alien(&ALIEN_RED_COLORS)),
+        SpriteId::SoldierRight => (UVec2::splat(13), alien(&ALIEN_GREEN_COLORS)),
+        SpriteId::SoldierTop => (UVec2::splat(13), alien(&ALIEN_GREY_COLORS)),
+        SpriteId::Boss => (UVec2::splat(13), vec![ // Intact, puis troué.
+            pixels(&ALIEN, &ALIEN_RED_COLORS, &[]), pixels(&ALIEN_FLAP, &ALIEN_RED_COLORS, &[]),
+            pixels(&ALIEN, &ALIEN_RED_COLORS, &CRACKS), pixels(&ALIEN_FLAP, &ALIEN_RED_COLORS, &CRACKS),
+        ]),
+        SpriteId::PlayerBullet => (UVec2::new(3, 9), vec![pixels(&BULLET, &PLAYER_BULLET_COLORS, &[])]),
+        SpriteId::EnemyBullet => (UVec2::new(3, 9), vec![pixels(&BULLET, &ENEMY_BULLET_COLORS, &[])]),
+        SpriteId::Explosion => (UVec2::splat(EXPLOSION_SIZE), (0..EXPLOSION_FRAMES).map(explosion).collect()),
+    };
+    Drawing { image: strip(size, &frames), frame_size: size, frames: frames.len() as u32 }
+}
+
+fn pixels(rows: &[&str], colors: &[(char, Rgba)], holes: &[(usize, usize)]) -> Vec<Rgba> { // Une lettre par pixel ; '.' (ou une lettre inconnue) reste transparent.
+    rows.iter().enumerate().flat_map(|(y, row)| row.chars().enumerate().map(move |(x, c)| (y, x, c)))
+        .map(|(y, x, c)| if holes.contains(&(y, x)) { CLEAR } else { colors.iter().find(|(key, _)| *key == c).map_or(CLEAR, |(_, rgba)| *rgba) })
+        .collect()
+}
+
+fn explosion(frame: usize) -> Vec<Rgba> { // Un cœur jaune, un anneau orange et des éclats rouges en étoile, qui grandissent et s'éteignent.
+    let center = (EXPLOSION_SIZE as f32 - 1.0) / 2.0;
+    let radius = 2.0 + frame as f32 * 1.2;
+    let mut data = Vec::with_capacity((EXPLOSION_SIZE * EXPLOSION_SIZE) as usize);
+    for y in 0..EXPLOSION_SIZE {
+        for x in 0..EXPLOSION_SIZE {
+            let offset = Vec2::new(x as f32 - center, y as f32 - center);
+            let distance = offset.length() / radius;
+            let spike = (offset.to_angle() * 8.0 + frame as f32 * 0.4).cos() > 0.4; // Huit branches qui tournent un peu.
+            data.push(if distance < 0.35 && frame < 3 { [255, 255, 220, 255] }
+                else if distance < 0.6 && frame < 5 { [253, 249, 0, 255] }
+                else if distance < 0.8 { [255, 161, 0, 255] }
+                else if distance < 1.0 && spike { [230, 41, 55, 255] }
+                else { CLEAR });
+        }
+    }
+    data
+}
+
+fn strip(size: UVec2, frames: &[Vec<Rgba>]) -> Image { // Les vignettes côte à côte, de gauche à droite.
+    let width = size.x * frames.len() as u32;
+    let mut data = Vec::with_capacity((width * size.y * 4) as usize);
+    for y in 0..size.y as usize {
+        for frame in frames {
+            for pixel in &frame[y * size.x as usize..(y + 1) * size.x as usize] { data.extend_from_slice(pixel); }
+        }
+    }
+    let mut image = Image::new(
+        Extent3d { width, height: size.y, depth_or_array_layers: 1 },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+    image.sampler = ImageSampler::nearest(); // Agrandi, un pixel reste un carré net.
+    image
+}
+
+pub fn pack_path(name: &str) -> String { format!("sprites/{}/pack.sprites.ron", name) } // Le manifeste d'un pack, d'après le nom de son dossier.
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sheet { pub size: UVec2, pub frames: Vec<URect>, pub clips: HashMap<String, AnimationClip> } // Une planche : la taille de l'image, la place de chaque vignette, et ses animations.
+
+impl Sheet {
+    pub fn grid(frame_size: UVec2, columns: u32, rows: u32, clips: HashMap<String, AnimationClip>) -> Self { // Des vignettes de même taille, de gauche à droite puis de haut en bas.
+        let frames = (0..rows).flat_map(|row| (0..columns).map(move |column| {
+            let min = UVec2::new(column, row) * frame_size;
+            URect::from_corners(min, min + frame_size)
+        })).collect();
+        Self { size: UVec2::new(columns, rows) * frame_size, frames, clips }
+    }
+
+    fn layout(&self) -> TextureAtlasLayout { // La planche telle que Bevy la découpe.
+        let mut layout = TextureAtlasLayout::new_empty(self.size);
+        for &frame in &self.frames { layout.add_texture(frame); }
+        layout
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct PackImage { pub file: String, pub sheet: Option<Sheet> } // Le fichier d'un rôle (chemin complet), et sa planche s'il en est une.
+
+#[derive(Asset, TypePath, Debug)]
+pub struct SpritePack { // Un pack chargé : ses images et le pack suivant de la chaîne.
+    pub name: String,
+    pub images: HashMap<SpriteId, PackImage>,
+    pub fallback: Option<Handle<SpritePack>>,
+}
+
+#[derive(Deserialize)]
+struct PackFile { // Le manifeste tel qu'il est écrit.
+    name: String,
+    #[serde(default)]
+    fallback: Option<String>, // Le dossier du pack suivant (sinon, Reserve-Sprites).
+    images: HashMap<SpriteId, ImageEntry>, // Les fichiers, relatifs au dossier du pack.
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ImageEntry { File(String), Sheet(SheetEntry) } // Un nom de fichier tout seul (une seule vignette), ou une planche.
+
+#[derive(Deserialize)]
+struct SheetEntry {
+    file: String,
+    frame_size: UVec2, // La taille d'une vignette, en pixels.
+    columns: u32,
+    #[serde(default)]
+    rows: Option<u32>, // Une seule ligne si on ne dit rien.
+    #[serde(default)]
+    clips: HashMap<String, ClipEntry>,
+}
+
+#[derive(Deserialize)]
+struct ClipEntry { frames: Vec<usize>, seconds: f32, #[serde(default)] looping: bool } // Les vignettes d'une animation, le temps de chacune, et si elle recommence.
+
+impl ImageEntry {
+    fn file(&self) -> &str { match self { ImageEntry::File(file) => file, ImageEntry::Sheet(sheet) => &sheet.file } }
+
+    fn check(&self) -> Result<(), String> { // Une planche cohérente : des vignettes qui existent et des temps positifs.
+        if self.file().trim().is_empty() { return Err("le nom de fichier est vide".into()); }
+        let ImageEntry::Sheet(sheet) = self else { return Ok(()) };
+        let count = sheet.columns * sheet.rows.unwrap_or(1);
+        if count == 0 || sheet.frame_size.min_element() == 0 { return Err("`frame_size`, `columns` et `rows` doivent être > 0".into()); }
+        for (name, clip) in &sheet.clips {
+            if clip.frames.is_empty() { return Err(format!("l'animation \"{}\" n'a pas de vignettes", name)); }
+            if let Some(frame) = clip.frames.iter().find(|&&frame| frame >= count as usize) { return Err(format!("l'animation \"{}\" montre la vignette {} sur {}", name, frame, count)); }
+            if clip.seconds <= 0.0 { return Err(format!("l'animation \"{}\" : `seconds` doit être > 0", name)); }
+        }
+        Ok(())
+    }
+
+    fn into_image(self, folder: &str) -> PackImage {
+        match self {
+            ImageEntry::File(file) => PackImage { file: format!("{}/{}", folder, file), sheet: None },
+            ImageEntry::Sheet(sheet) => PackImage {
+                file: format!("{}/{}", folder, sheet.file),
+                sheet: Some(Sheet::grid(
+                    sheet.frame_size,
+                    sheet.columns,
+                    sheet.rows.unwrap_or(1),
+                    sheet.clips.into_iter().map(|(name, clip)| (name, AnimationClip::uniform(clip.frames, clip.seconds, clip.looping))).collect(),
+                )),
+            },
+        }
+    }
+}
+
+#[derive(Default, TypePath)]
+pub struct SpritePackLoader; // Le lecteur des fichiers `.sprites.ron`.
+
+impl AssetLoader for SpritePackLoader {
+    type Asset = SpritePack;
+    type Settings = ();
+    type Error = LevelLoadError;
+
+    async fn load(&self, reader: &mut dyn Reader, _settings: &(), load_context: &mut LoadContext<'_>) -> Result<SpritePack, LevelLoadError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let path = load_context.path().display().to_string();
+        let text = String::from_utf8_lossy(&bytes);
+        let file: PackFile = levels::parse(&text, &path)?;
+        let folder = load_context.path().parent().map(|p| p.to_string_lossy().replace('\\', "/")).unwrap_or_default();
+        let own_name = folder.rsplit('/').next().unwrap_or_default().to_string();
+        let invalid = |key: &str, message: String| LevelLoadError::Invalid {
+            path: path.clone(), line: text.find(key).map(|i| levels::line_of(&text, i)).unwrap_or(1), message,
+        };
+        for (id, entry) in &file.images {
+            entry.check().map_err(|message| invalid(&format!("{:?}:", id), format!("`{:?}` : {}", id, message)))?;
+        }
+        if file.fallback.as_deref() == Some(own_name.as_str()) {
+            return Err(invalid("fallback:", "`fallback` ne peut pas être le pack lui-même".into()));
+        }
+        let fallback = match file.fallback {
+            Some(name) => Some(name),
+            None if own_name != RESERVE_PACK => Some(RESERVE_PACK.to_string()), // Toutes les chaînes finissent par la réserve.
+            None => None,
+        };
+        let mut images = HashMap::new();
+        for (id, entry) in file.images {
+            let image = match entry {
+                ImageEntry::File(export) if export.ends_with(".json") => { // Un export Aseprite : son lecteur fait la planche.
+                    let export = format!("{}/{}", folder, export);
+                    let sheet = match load_context.loader().immediate().load::<AsepriteSheet>(export).await {
+                        Ok(sheet) => sheet,
+                        Err(LoadDirectError::LoadError { error: AssetLoadError::AssetReaderError(AssetReaderError::NotFound(_)), .. }) => continue, // Pas encore exporté : le pack suivant le fournit.
+                        Err(err) => return Err(invalid(&format!("{:?}:", id), format!("`{:?}` : {}", id, err))),
+                    };
+                    sheet.take().into_image()
+                }
+                entry => entry.into_image(&folder),
+            };
+            images.insert(id, image);
+        }
+        Ok(SpritePack {
+            name: file.name,
+            images,
+            fallback: fallback.map(|name| load_context.load(pack_path(&name))), // Le pack suivant est chargé avec celui-ci.
+        })
+    }
+
+    fn extensions(&self) -> &[&str] { &["sprites.ron"] }
+}
+
+fn chain<'a>(packs: &'a Assets<SpritePack>, first: &Handle<SpritePack>) -> Vec<&'a SpritePack> { // Les packs à essayer, dans l'ordre.
+    let mut visited = HashSet::new(); // Une chaîne qui boucle (a -> b -> a) s'arrête au deuxième passage.
+    let mut packs_in_order = Vec::new();
+    let mut next = Some(first);
+    while let Some(handle) = next {
+        if !visited.insert(handle.id()) { break; }
+        let Some(pack) = packs.get(handle) else { break }; // Pas encore chargé (ou refusé) : la chaîne s'arrête là.
+        packs_in_order.push(pack);
+        next = pack.fallback.as_ref();
+    }
+    packs_in_order
+}
+
+fn find_image<'a>(chain: &[&'a SpritePack], id: SpriteId, usable: impl Fn(&str) -> bool) -> Option<&'a PackImage> { // Le premier fichier utilisable le long de la chaîne.
+    chain.iter().find_map(|pack| pack.images.get(&id).filter(|image| usable(&image.file)))
+}
+
+#[derive(Clone)]
+struct Look { image: Handle<Image>, atlas: Option<Handle<TextureAtlasLayout>>, clips: HashMap<String, AnimationClip> } // L'habit d'un rôle : son image, sa planche et ses animations.
+
+#[derive(Resource)]
+pub struct Sprites { // Là où les systèmes prennent leurs images.
+    selected: Option<(String, Handle<SpritePack>)>, // Le pack choisi dans les réglages.
+    looks: HashMap<SpriteId, Look>, // L'habit de chaque rôle, trouvé le long de la chaîne.
+    drawings: HashMap<SpriteId, Look>, // Les dessins du jeu, en dernier recours.
+    broken: HashSet<String>, // Les fichiers absents ou illisibles.
+}
+
+impl FromWorld for Sprites {
+    fn from_world(world: &mut World) -> Self { // Les dessins sont prêts tout de suite, avant même le premier pack.
+        let drawings: Vec<(SpriteId, Drawing)> = ALL_SPRITES.iter().map(|&id| (id, drawing(id))).collect();
+        let mut layouts = world.resource_mut::<Assets<TextureAtlasLayout>>();
+        let atlases: Vec<_> = drawings.iter()
+            .map(|(_, d)| (d.frames > 1).then(|| layouts.add(TextureAtlasLayout::from_grid(d.frame_size, d.frames, 1, None, None))))
+            .collect();
+        let mut images = world.resource_mut::<Assets<Image>>();
+        let drawings = drawings.into_iter().zip(atlases)
+            .map(|((id, d), atlas)| (id, Look { image: images.add(d.image), atlas, clips: HashMap::new() }))
+            .collect();
+        Self { selected: None, looks: HashMap::new(), drawings, broken: HashSet::new() }
+    }
+}
+
+impl Sprites {
+    fn look(&self, id: SpriteId) -> &Look { self.looks.get(&id).unwrap_or(&self.drawings[&id]) } // Celui du pack, sinon le dessin.
+
+    pub fn image(&self, id: SpriteId) -> Handle<Image> { self.look(id).image.clone() }
+
+    pub fn sprite(&self, id: SpriteId) -> Sprite { // L'image d'un rôle, sur sa première vignette si c'est une planche.
+        Sprite { image: self.image(id), texture_atlas: self.atlas(id), ..default() }
+    }
+
+    pub fn icon(&self, id: SpriteId) -> ImageNode { // Pareil, pour l'interface.
+        ImageNode { image: self.image(id), texture_atlas: self.atlas(id), ..default() }
+    }
+
+    fn atlas(&self, id: SpriteId) -> Option<TextureAtlas> { self.look(id).atlas.clone().map(TextureAtlas::from) }
+
+    pub fn clip(&self, id: SpriteId, name: &str) -> Option<&AnimationClip> { self.look(id).clips.get(name) }
+
+    fn resolve(&mut self, packs: &Assets<SpritePack>, asset_server: &AssetServer, layouts: &mut Assets<TextureAtlasLayout>) { // On refait la liste des habits en suivant la chaîne.
+        let Some((_, first)) = &self.selected else { return };
+        let chain = chain(packs, first);
+        let mut looks = HashMap::new();
+        for id in ALL_SPRITES {
+            match find_image(&chain, id, |file| !self.broken.contains(file)) { // Un fichier absent se signale en échouant, et on passe au suivant.
+                Some(found) => {
+                    let atlas = found.sheet.as_ref().map(|sheet| layouts.add(sheet.layout()));
+                    let clips = found.sheet.as_ref().map(|sheet| sheet.clips.clone()).unwrap_or_default();
+                    looks.insert(id, Look { image: asset_server.load(found.file.clone()), atlas, clips });
+                }
+                None => { // Le dessin du jeu, avec les animations de la réserve (il est rangé comme elle).
+                    let reserve = chain.last().filter(|pack| pack.fallback.is_none());
+                    let clips = reserve.and_then(|pack| pack.images.get(&id)?.sheet.as_ref()).map(|sheet| sheet.clips.clone()).unwrap_or_default();
+                    looks.insert(id, Look { clips, ..self.drawings[&id].clone() });
+                }
+            }
+        }
+        self.looks = looks;
+    }
+}
+
+pub fn sprites_ready(sprites: Res<Sprites>, asset_server: Res<AssetServer>) -> bool { // Le pack choisi, toute sa chaîne et les images trouvées sont-ils en place (ou refusés) ?
+    let Some((_, pack)) = &sprites.selected else { return false };
+    match asset_server.recursive_dependency_load_state(pack) {
+        RecursiveDependencyLoadState::Loaded => !sprites.looks.is_empty() && sprites.looks.values().all(|look| {
+            asset_server.get_load_state(&look.image).is_none_or(|state| state.is_loaded()) // Un dessin du jeu n'est pas un fichier ; une image refusée sera remplacée.
+        }),
+        RecursiveDependencyLoadState::Failed(_) => true,
+        _ => false,
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn sprite_pack_system( // Le pack choisi, ses fichiers modifiés ou une image illisible : on rhabille tout.
+    tuning: Res<Tuning>,
+    asset_server: Res<AssetServer>,
+    packs: Res<Assets<SpritePack>>,
+    mut layouts: ResMut<Assets<TextureAtlasLayout>>,
+    mut sprites: ResMut<Sprites>,
+    mut status: ResMut<StatusMessage>,
+    mut pack_events: MessageReader<AssetEvent<SpritePack>>,
+    mut pack_failures: MessageReader<AssetLoadFailedEvent<SpritePack>>,
+    mut image_failures: MessageReader<AssetLoadFailedEvent<Image>>,
+    mut skins: Query<(&Skin, &mut Sprite)>,
+    mut icons: Query<(&Skin, &mut ImageNode)>,
+) {
+    let mut switched = false;
+    if sprites.selected.as_ref().is_none_or(|(name, _)| *name != tuning.sprite_pack) { // Un autre pack dans les réglages.
+        switched = sprites.selected.is_some();
+        sprites.selected = Some((tuning.sprite_pack.clone(), asset_server.load(pack_path(&tuning.sprite_pack))));
+    }
+    let mut refresh = switched;
+    for event in pack_events.read() {
+        refresh |= matches!(event, AssetEvent::Added { .. } | AssetEvent::LoadedWithDependencies { .. } | AssetEvent::Modified { .. }); // Added : le pack est là, même si la suite de la chaîne est refusée.
+    }
+    for failure in pack_failures.read() {
+        status.show(format!("Pack d'images refusé ({}) : {}", failure.path.path().display(), failure.error));
+    }
+    for failure in image_failures.read() { // Le fichier manque ou ne se lit pas : on passe au pack suivant.
+        let file = failure.path.path().to_string_lossy().replace('\\', "/");
+        if sprites.looks.values().any(|look| look.image.id() == failure.id) {
+            warn!("{} absent ou illisible ({}) : on prend l'image suivante de la chaîne", file, failure.error);
+            sprites.broken.insert(file);
+            refresh = true;
+        }
+    }
+    let Some((_, first)) = &sprites.selected else { return };
+    let Some(pack) = packs.get(first) else { return }; // On garde les anciens habits tant que le nouveau pack n'est pas là.
+    if !refresh { return; }
+    let name = pack.name.clone();
+    sprites.resolve(&packs, &asset_server, &mut layouts);
+    for (skin, mut sprite) in skins.iter_mut() { *sprite = Sprite { color: sprite.color, custom_size: sprite.custom_size, ..sprites.sprite(skin.0) }; }
+    for (skin, mut icon) in icons.iter_mut() { *icon = sprites.icon(skin.0); }
+    if switched { status.show(format!("Pack d'images : {}", name)); }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_drawing_is_a_full_rectangle() { // Une ligne trop courte décalerait tout le dessin.
+        for (rows, name) in [(&SHIP[..], "vaisseau"), (&ALIEN[..], "alien"), (&ALIEN_FLAP[..], "alien qui bat des ailes"), (&BULLET[..], "balle")] {
+            assert!(rows.iter().all(|row| row.len() == rows[0].len()), "{} : lignes de longueurs différentes", name);
+        }
+    }
+
+    #[test]
+    fn every_sprite_has_a_visible_drawing() {
+        for id in ALL_SPRITES {
+            let drawing = drawing(id);
+            let data = drawing.image.data.as_ref().expect("des pixels");
+            assert_eq!(drawing.image.size(), UVec2::new(drawing.frame_size.x * drawing.frames, drawing.frame_size.y), "{:?}", id);
+            assert_eq!(data.len(), (drawing.image.width() * drawing.image.height() * 4) as usize, "{:?}", id);
+            assert!(data.chunks(4).any(|pixel| pixel[3] > 0), "{:?} est tout transparent", id);
+        }
+    }
+
+    #[test]
+    fn the_ship_keeps_its_two_to_one_shape() { // Comme `player_size` (30 x 15) : le dessin n'est pas écrasé.
+        assert_eq!(drawing(SpriteId::Player).frame_size, UVec2::new(15, 8));
+    }
+
+    fn pack(packs: &mut Assets<SpritePack>, images: &[(SpriteId, &str)], fallback: Option<&Handle<SpritePack>>) -> Handle<SpritePack> {
+        let images = images.iter().map(|(id, file)| (*id, PackImage { file: file.to_string(), sheet: None })).collect();
+        packs.add(SpritePack { name: "test".into(), images, fallback: fallback.cloned() })
+    }
+
+    fn find<'a>(packs: &'a Assets<SpritePack>, first: &Handle<SpritePack>, id: SpriteId, usable: impl Fn(&str) -> bool) -> Option<&'a str> {
+        find_image(&chain(packs, first), id, usable).map(|image| image.file.as_str())
+    }
+
+    #[test]
+    fn a_missing_image_is_taken_from_the_next_pack() {
+        let mut packs = Assets::<SpritePack>::default();
+        let reserve = pack(&mut packs, &[(SpriteId::Player, "reserve/ship.png"), (SpriteId::Boss, "reserve/boss.png")], None);
+        let classic = pack(&mut packs, &[(SpriteId::Player, "classic/ship.png"), (SpriteId::Boss, "classic/boss.png")], Some(&reserve));
+        let xgalaga = pack(&mut packs, &[(SpriteId::Player, "xgalaga/ship.png")], Some(&classic));
+        let present = |file: &str| file != "classic/boss.png"; // Ce fichier-là n'a pas été fourni.
+        assert_eq!(find(&packs, &xgalaga, SpriteId::Player, present), Some("xgalaga/ship.png"));
+        assert_eq!(find(&packs, &xgalaga, SpriteId::Boss, present), Some("reserve/boss.png"));
+        assert_eq!(find(&packs, &xgalaga, SpriteId::Explosion, present), None, "personne ne l'a : ce sera le dessin");
+    }
+
+    #[test]
+    fn a_looping_chain_stops() {
+        let mut packs = Assets::<SpritePack>::default();
+        let first = pack(&mut packs, &[], None);
+        let second = pack(&mut packs, &[], Some(&first));
+        packs.get_mut(&first).unwrap().fallback = Some(second);
+        assert_eq!(find(&packs, &first, SpriteId::Player, |_| true), None);
+    }
+
+    fn shipped_pack(name: &str) -> PackFile {
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("assets").join(pack_path(name));
+        let text = std::fs::read_to_string(&path).expect("le manifeste");
+        levels::parse(&text, &path.display().to_string()).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    #[test]
+    fn shipped_packs_read_and_the_reserve_knows_every_sprite() {
+        for name in ["classic", "xgalaga", "hires", RESERVE_PACK] {
+            for (id, entry) in shipped_pack(name).images { entry.check().unwrap_or_else(|err| panic!("{} {:?} : {}", name, id, err)); }
+        }
+        let reserve = shipped_pack(RESERVE_PACK);
+        assert!(ALL_SPRITES.iter().all(|id| reserve.images.contains_key(id)), "la réserve doit tout avoir");
+    }
+
+    #[test]
+    fn the_drawings_are_laid_out_like_the_reserve() { // Les animations de la réserve servent aussi aux dessins.
+        for (id, entry) in shipped_pack(RESERVE_PACK).images {
+            let frames = match entry { ImageEntry::File(_) => 1, ImageEntry::Sheet(sheet) => sheet.columns * sheet.rows.unwrap_or(1) };
+            assert_eq!(drawing(id).frames, frames, "{:?}", id);
+        }
+    }
+}
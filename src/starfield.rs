@@ -0,0 +1,186 @@
+// ═══════════════════════════════════════════════════════════════════════════
+// 🌌 LE CIEL ÉTOILÉ : des couches d'étoiles qui défilent derrière la partie
+// ═══════════════════════════════════════════════════════════════════════════
+//
+// Le fond n'est plus un simple noir : plusieurs couches d'étoiles descendent
+// l'écran. Les lointaines sont petites, lentes et bleutées, les proches plus
+// grosses, rapides et blanches : c'est ce décalage (la parallaxe) qui donne
+// la profondeur. Chaque étoile scintille à son propre rythme.
+//
+//   - Quand un niveau est fini (`WaveState::LevelCompleted`), tout accélère
+//     jusqu'à `warp` fois la vitesse normale et les étoiles s'étirent en
+//     traits : le saut vers le niveau suivant.
+//   - En pause, le ciel se fige.
+//   - Chaque niveau peut avoir son ciel (`starfield:` dans son fichier) ;
+//     sans rien, c'est le ciel par défaut ci-dessous. Le ciel est refait quand
+//     on change de niveau, ou quand on modifie le fichier en jouant.
+//
+// Les étoiles ont leur propre hasard, pas celui de la partie : le ciel ne
+// change rien aux replays.
+
+use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::Deserialize;
+
+use crate::levels::Levels;
+use crate::playfield::Playfield;
+use crate::{WaveManager, WaveState};
+
+const MAX_STARS: usize = 2000; // Au-delà, le fond coûterait plus cher que la partie.
+const STAR_Z: f32 = -100.0; // Derrière tout le reste ; la couche 0 est la plus loin.
+const WARP_EASE: f32 = 2.5; // La vitesse à laquelle le ciel accélère (et ralentit), par seconde.
+const STREAK_SECONDS: f32 = 0.05; // Pendant le saut, une étoile s'étire sur le chemin qu'elle fait en 0,05 s.
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct StarfieldDef { // Le ciel d'un niveau.
+    pub layers: Vec<StarLayer>, // De la plus lointaine à la plus proche.
+    #[serde(default = "default_warp")]
+    pub warp: f32, // Combien de fois plus vite pendant le saut vers le niveau suivant.
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct StarLayer { // Une couche d'étoiles qui vont toutes à la même vitesse.
+    pub count: usize,
+    pub speed: f32, // En pixels par seconde, vers le bas.
+    pub size: f32, // Le côté d'une étoile, en pixels.
+    pub color: (f32, f32, f32), // Rouge, vert, bleu, entre 0 et 1.
+    #[serde(default)]
+    pub twinkle: f32, // 0 : l'étoile reste fixe ; 1 : elle s'éteint complètement en scintillant.
+}
+
+fn default_warp() -> f32 { 8.0 }
+
+impl Default for StarfieldDef {
+    fn default() -> Self { // Le ciel de l'arcade : trois couches.
+        Self {
+            layers: vec![
+                StarLayer { count: 70, speed: 15.0, size: 1.5, color: (0.45, 0.5, 0.85), twinkle: 0.7 },
+                StarLayer { count: 40, speed: 40.0, size: 2.0, color: (0.75, 0.75, 0.95), twinkle: 0.4 },
+                StarLayer { count: 15, speed: 90.0, size: 3.0, color: (1.0, 1.0, 1.0), twinkle: 0.2 },
+            ],
+            warp: default_warp(),
+        }
+    }
+}
+
+impl StarfieldDef {
+    pub fn check(&self) -> Result<(), String> { // Un ciel qui a du sens.
+        if self.warp < 1.0 { return Err("`starfield.warp` doit être >= 1".into()); }
+        if self.layers.iter().map(|layer| layer.count).sum::<usize>() > MAX_STARS { return Err(format!("`starfield` : {} étoiles au maximum", MAX_STARS)); }
+        for (i, layer) in self.layers.iter().enumerate() {
+            let (r, g, b) = layer.color;
+            if layer.speed < 0.0 || layer.size <= 0.0 { return Err(format!("couche d'étoiles {} : `speed` doit être >= 0 et `size` > 0", i + 1)); }
+            if ![r, g, b, layer.twinkle].iter().all(|v| (0.0..=1.0).contains(v)) { return Err(format!("couche d'étoiles {} : `color` et `twinkle` doivent être entre 0 et 1", i + 1)); }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Component, Clone, Copy, Debug)]
+pub struct Star { layer: usize, phase: f32, rate: f32 } // Sa couche, et son scintillement (décalage et vitesse).
+
+#[derive(Resource, Debug)]
+pub struct Sky { def: Option<StarfieldDef>, warp: f32, clock: f32 } // Le ciel affiché, la vitesse du moment (1 = normale) et son chrono.
+
+impl Default for Sky {
+    fn default() -> Self { Self { def: None, warp: 1.0, clock: 0.0 } }
+}
+
+fn ease(current: f32, target: f32, seconds: f32) -> f32 { current + (target - current) * (1.0 - (-WARP_EASE * seconds).exp()) } // On s'approche de la cible sans à-coup.
+
+fn brightness(star: &Star, twinkle: f32, clock: f32) -> f32 { 1.0 - twinkle * (0.5 + 0.5 * (clock * star.rate + star.phase).sin()) } // Entre 1 et 1 - twinkle.
+
+pub fn sky_system( // Le ciel du niveau en cours : on le refait s'il a changé.
+    mut commands: Commands,
+    levels: Levels,
+    wave_mgr: Res<WaveManager>,
+    playfield: Res<Playfield>,
+    mut sky: ResMut<Sky>,
+    stars: Query<Entity, With<Star>>,
+) {
+    let def = levels.level(wave_mgr.current_level).map(|level| level.starfield.clone()).unwrap_or_default(); // Le ciel par défaut tant que les niveaux ne sont pas chargés.
+    if sky.def.as_ref() == Some(&def) { return; }
+    for star in stars.iter() { commands.entity(star).despawn(); }
+    let mut rng = StdRng::seed_from_u64(wave_mgr.current_level as u64); // Le même ciel à chaque fois qu'on revient à ce niveau.
+    let half = playfield.size / 2.0;
+    for (index, layer) in def.layers.iter().enumerate() {
+        let (r, g, b) = layer.color;
+        for _ in 0..layer.count {
+            let position = Vec2::new(rng.gen_range(-half.x..=half.x), rng.gen_range(-half.y..=half.y));
+            commands.spawn((
+                Star { layer: index, phase: rng.gen_range(0.0..std::f32::consts::TAU), rate: rng.gen_range(1.5..5.0) },
+                Sprite { color: Color::srgb(r, g, b), custom_size: Some(Vec2::splat(layer.size)), ..default() },
+                Transform::from_translation(position.extend(STAR_Z + index as f32)),
+            ));
+        }
+    }
+    sky.def = Some(def);
+}
+
+pub fn starfield_system( // Les étoiles descendent, scintillent, et filent pendant le saut.
+    time: Res<Time>,
+    wave_mgr: Res<WaveManager>,
+    playfield: Res<Playfield>,
+    mut sky: ResMut<Sky>,
+    mut stars: Query<(&Star, &mut Transform, &mut Sprite)>,
+) {
+    let seconds = time.delta_secs();
+    let sky = &mut *sky;
+    let Some(def) = &sky.def else { return };
+    let target = if wave_mgr.state == WaveState::LevelCompleted { def.warp } else { 1.0 };
+    sky.warp = ease(sky.warp, target, seconds);
+    sky.clock += seconds;
+    let half = playfield.size / 2.0;
+    for (star, mut transform, mut sprite) in stars.iter_mut() {
+        let Some(layer) = def.layers.get(star.layer) else { continue };
+        let speed = layer.speed * sky.warp;
+        let length = layer.size + layer.speed * (sky.warp - 1.0) * STREAK_SECONDS; // Un point, ou un trait pendant le saut.
+        let position = &mut transform.translation;
+        position.y -= speed * seconds;
+        if position.y < -half.y - length { position.y += playfield.height() + 2.0 * length; } // Sortie en bas : elle revient en haut.
+        position.x = (position.x + half.x).rem_euclid(playfield.width()) - half.x; // La fenêtre a pu rétrécir.
+        sprite.custom_size = Some(Vec2::new(layer.size, length));
+        sprite.color.set_alpha(brightness(star, layer.twinkle, sky.clock));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_default_sky_is_valid_and_far_stars_are_slower() {
+        let def = StarfieldDef::default();
+        assert_eq!(def.check(), Ok(()));
+        assert!(def.layers.windows(2).all(|pair| pair[0].speed < pair[1].speed), "la parallaxe : les lointaines d'abord");
+    }
+
+    #[test]
+    fn nonsense_skies_are_refused() {
+        let mut def = StarfieldDef { warp: 0.5, ..default() };
+        assert!(def.check().is_err());
+        def.warp = 4.0;
+        def.layers[1].twinkle = 2.0;
+        assert!(def.check().unwrap_err().contains("couche d'étoiles 2"));
+    }
+
+    #[test]
+    fn the_warp_builds_up_smoothly() {
+        let mut warp = 1.0;
+        for _ in 0..30 { warp = ease(warp, 8.0, 1.0 / 60.0); }
+        assert!(warp > 4.0 && warp < 8.0, "{}", warp);
+        for _ in 0..600 { warp = ease(warp, 1.0, 1.0 / 60.0); }
+        assert!((warp - 1.0).abs() < 0.01, "{}", warp);
+    }
+
+    #[test]
+    fn twinkling_stays_in_range() {
+        let star = Star { layer: 0, phase: 1.0, rate: 3.0 };
+        for tick in 0..200 {
+            let light = brightness(&star, 0.7, tick as f32 / 60.0);
+            assert!((0.3..=1.0).contains(&light), "{}", light);
+        }
+        assert_eq!(brightness(&star, 0.0, 2.0), 1.0);
+    }
+}
@@ -0,0 +1,97 @@
+// ═══════════════════════════════════════════════════════════════════════════
+// 📊 LES STATISTIQUES ET LES SUCCÈS : ce que le joueur a fait pendant la partie
+// ═══════════════════════════════════════════════════════════════════════════
+//
+// Deux écouteurs des messages du jeu (voir messages.rs) :
+//
+//   - les statistiques comptent les aliens abattus, les vaisseaux perdus, les
+//     bonus attrapés et les vagues finies ; elles repartent à zéro à chaque
+//     partie et s'affichent dans le bilan sans fenêtre ;
+//   - les succès s'allument une fois pour toute la session ("Premier chef
+//     abattu", "Vague parfaite"...) avec un petit message en bas de l'écran.
+
+use bevy::prelude::*;
+
+use crate::messages::{EnemyKilled, PlayerHit, PowerUpCollected, WaveCleared};
+use crate::tuning::StatusMessage;
+use crate::EnemyType;
+
+const BOSS_HUNTER_KILLS: u32 = 5; // Les chefs à abattre pour le succès "Chasseur de chefs".
+const COLLECTOR_POWER_UPS: u32 = 3; // Les bonus à attraper pour le succès "Collectionneur".
+
+#[derive(Resource, Default, Clone, Debug, PartialEq)]
+pub struct GameStats { // Les compteurs de la partie en cours.
+    pub kills: u32, // Les aliens abattus (chefs compris).
+    pub bosses: u32, // Les chefs abattus.
+    pub diving_kills: u32, // Les aliens abattus en plein piqué.
+    pub ships_lost: u32, // Les vaisseaux (ou ailiers) détruits.
+    pub power_ups: u32, // Les bonus attrapés.
+    pub waves_cleared: u32, // Les vagues finies (stages bonus compris).
+    pub perfect_waves: u32, // Les vagues où on a tout abattu soi-même.
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Achievement { FirstBlood, BossHunter, DiveBomber, Perfectionist, Collector } // Les succès qu'on peut débloquer.
+
+impl Achievement {
+    pub fn title(self) -> &'static str {
+        match self {
+            Achievement::FirstBlood => "Premier alien abattu",
+            Achievement::BossHunter => "Chasseur de chefs",
+            Achievement::DiveBomber => "Abattu en plein piqué",
+            Achievement::Perfectionist => "Vague parfaite",
+            Achievement::Collector => "Collectionneur",
+        }
+    }
+
+    fn earned(self, stats: &GameStats) -> bool { // Les statistiques de la partie suffisent-elles ?
+        match self {
+            Achievement::FirstBlood => stats.kills >= 1,
+            Achievement::BossHunter => stats.bosses >= BOSS_HUNTER_KILLS,
+            Achievement::DiveBomber => stats.diving_kills >= 1,
+            Achievement::Perfectionist => stats.perfect_waves >= 1,
+            Achievement::Collector => stats.power_ups >= COLLECTOR_POWER_UPS,
+        }
+    }
+}
+
+const ALL_ACHIEVEMENTS: [Achievement; 5] = [Achievement::FirstBlood, Achievement::BossHunter, Achievement::DiveBomber, Achievement::Perfectionist, Achievement::Collector];
+
+#[derive(Resource, Default)]
+pub struct Achievements { pub unlocked: Vec<Achievement> } // Les succès déjà débloqués pendant cette session.
+
+impl GameStats {
+    pub fn summary(&self) -> String { // Une ligne pour le bilan sans fenêtre.
+        format!("{} aliens abattus ({} chefs, {} en piqué), {} vaisseaux perdus, {} bonus, {} vagues finies ({} parfaites)",
+            self.kills, self.bosses, self.diving_kills, self.ships_lost, self.power_ups, self.waves_cleared, self.perfect_waves)
+    }
+}
+
+pub fn stats_system( // On compte tout ce qui se passe.
+    mut stats: ResMut<GameStats>,
+    mut killed: MessageReader<EnemyKilled>,
+    mut player_hit: MessageReader<PlayerHit>,
+    mut collected: MessageReader<PowerUpCollected>,
+    mut cleared: MessageReader<WaveCleared>,
+) {
+    for kill in killed.read() {
+        stats.kills += 1;
+        if kill.kind == EnemyType::Boss { stats.bosses += 1; }
+        if kill.diving { stats.diving_kills += 1; }
+    }
+    stats.ships_lost += player_hit.read().count() as u32;
+    stats.power_ups += collected.read().count() as u32;
+    for wave in cleared.read() {
+        stats.waves_cleared += 1;
+        if wave.perfect { stats.perfect_waves += 1; }
+    }
+}
+
+pub fn achievements_system(stats: Res<GameStats>, mut achievements: ResMut<Achievements>, mut status: ResMut<StatusMessage>) { // Un succès s'allume dès que les statistiques le permettent.
+    if !stats.is_changed() { return; }
+    for achievement in ALL_ACHIEVEMENTS {
+        if achievements.unlocked.contains(&achievement) || !achievement.earned(&stats) { continue; }
+        achievements.unlocked.push(achievement);
+        status.show(format!("Succès débloqué : {}", achievement.title()));
+    }
+}
@@ -0,0 +1,180 @@
+// ═══════════════════════════════════════════════════════════════════════════
+// 🧲 LE RAYON TRACTEUR : le chef capture ton vaisseau... et tu peux le sauver !
+// ═══════════════════════════════════════════════════════════════════════════
+//
+// Le grand truc de Galaga : un chef descend, s'arrête, et allume un rayon
+// bleu. Si ton vaisseau passe dedans, il est aspiré et devient prisonnier : il
+// suit le chef partout. Si tu abats ce chef, le prisonnier est libéré, il
+// vient se coller à côté de ton vaisseau et tu pilotes un double chasseur
+// (deux fois plus de tirs, mais deux fois plus large !).
+
+use bevy::prelude::*;
+
+use crate::animation::{self, Animation};
+use crate::collider::Collider;
+use crate::formation::{Flight, Formation, FormationSlot};
+use crate::interpolation::Interpolated;
+use crate::paths::{PathFollower, Spline};
+use crate::sprites::{Skin, SpriteId, Sprites};
+use crate::tuning::Tuning;
+use crate::lives::Lives;
+use crate::{Enemy, InGame, Movable, Player};
+
+const PARK_HEIGHT: f32 = 230.0; // Le chef s'arrête à cette hauteur au-dessus du joueur.
+const BEAM_HALF_WIDTH: f32 = 40.0; // La moitié de la largeur du rayon.
+const BEAM_OPENING: f32 = 0.6; // Le temps que met le rayon pour descendre jusqu'en bas.
+const BEAM_DURATION: f32 = 3.0; // Le temps total pendant lequel le rayon reste allumé.
+const CAPTURE_SPEED: f32 = 120.0; // La vitesse à laquelle ton vaisseau est aspiré.
+const CAPTIVE_OFFSET: Vec2 = Vec2::new(0.0, 40.0); // Le prisonnier vole juste au-dessus du chef.
+const RESCUE_SPEED: f32 = 250.0; // La vitesse du vaisseau libéré qui vient se coller à toi.
+
+#[derive(Component)] pub struct TractorDive; // Ce chef plonge pour capturer, pas pour foncer.
+#[derive(Component)] pub struct TractorBeam { timer: Timer, beam: Entity } // Le rayon est allumé.
+#[derive(Component)] pub struct BeamSprite; // Le dessin du rayon, accroché sous le chef.
+#[derive(Component)] pub struct BeingCaptured { boss: Entity } // Ton vaisseau est en train d'être aspiré.
+#[derive(Component)] #[require(Interpolated, DespawnOnExit<InGame>)] pub struct CapturedFighter { boss: Entity } // Un vaisseau prisonnier qui suit son chef.
+#[derive(Component)] #[require(Interpolated, DespawnOnExit<InGame>)] pub struct RescuedFighter; // Un vaisseau libéré qui vient se coller au joueur.
+#[derive(Component)] pub struct DualFighter { pub wingman: Entity } // Le joueur pilote deux vaisseaux collés.
+
+impl Tuning {
+    pub fn dual_offset(&self) -> f32 { self.player_size.x } // Le deuxième vaisseau se colle juste à droite du premier.
+}
+
+pub fn tractor_path(start: Vec2, player: Vec2, side: f32) -> Spline { // Le chef descend se garer au-dessus du joueur.
+    let park = Vec2::new(player.x, player.y + PARK_HEIGHT);
+    Spline::CatmullRom(vec![start, start + Vec2::new(60.0 * side, -40.0), park + Vec2::new(0.0, 80.0), park])
+}
+
+#[allow(clippy::type_complexity)]
+pub fn tractor_park_system( // Le chef est arrivé à sa place de parking : il allume le rayon.
+    mut commands: Commands,
+    boss_q: Query<(Entity, &PathFollower), (With<TractorDive>, Without<TractorBeam>)>,
+) {
+    for (entity, follower) in boss_q.iter() {
+        if !follower.finished() { continue; }
+        let beam = commands.spawn(( // Le rayon est un grand rectangle bleu transparent sous le chef.
+            BeamSprite,
+            Sprite { color: Color::srgba(0.3, 0.6, 1.0, 0.45), custom_size: Some(Vec2::new(BEAM_HALF_WIDTH * 2.0, 0.0)), ..default() },
+            Transform::from_xyz(0.0, 0.0, -0.5),
+        )).id();
+        commands.entity(entity).remove::<PathFollower>().add_child(beam).insert(TractorBeam { timer: Timer::from_seconds(BEAM_DURATION, TimerMode::Once), beam });
+    }
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn tractor_beam_system( // Le rayon descend, aspire le joueur s'il passe dessous, puis s'éteint.
+    mut commands: Commands,
+    time: Res<Time>,
+    tuning: Res<Tuning>,
+    formation: Res<Formation>,
+    mut boss_q: Query<(Entity, &Transform, &FormationSlot, &mut Flight, &mut TractorBeam), With<Enemy>>,
+    mut beam_q: Query<(&mut Sprite, &mut Transform), (With<BeamSprite>, Without<Enemy>, Without<Player>)>,
+    mut player_q: Query<(Entity, &Transform, &mut Movable, Has<BeingCaptured>, Has<DualFighter>), With<Player>>,
+    captive_q: Query<(), With<CapturedFighter>>,
+) {
+    let player = player_q.single_mut().ok();
+    let mut player = player.map(|(e, t, m, captured, dual)| (e, t.translation.xy(), m, captured, dual));
+    for (boss, b_trans, slot, mut flight, mut beam) in boss_q.iter_mut() {
+        beam.timer.tick(time.delta());
+        let b_pos = b_trans.translation.xy();
+        let length = match &player { Some((_, p_pos, ..)) => (b_pos.y - p_pos.y + tuning.player_size.y).max(0.0), None => PARK_HEIGHT };
+        let reach = length * (beam.timer.elapsed_secs() / BEAM_OPENING).min(1.0); // Le rayon descend petit à petit.
+        if let Ok((mut sprite, mut trans)) = beam_q.get_mut(beam.beam) {
+            sprite.custom_size = Some(Vec2::new(BEAM_HALF_WIDTH * 2.0, reach));
+            trans.translation.y = -reach / 2.0;
+        }
+
+        if let Some((p_ent, p_pos, movable, captured, dual)) = player.as_mut() { // Le joueur est-il sous le rayon ?
+            let fully_open = beam.timer.elapsed_secs() >= BEAM_OPENING;
+            if fully_open && !*captured && !*dual && captive_q.is_empty() && (p_pos.x - b_pos.x).abs() < BEAM_HALF_WIDTH {
+                commands.entity(*p_ent).insert(BeingCaptured { boss });
+                movable.velocity = Vec2::ZERO; // On ne contrôle plus rien, on est aspiré.
+                *captured = true;
+            }
+        }
+
+        if beam.timer.is_finished() { // Le rayon s'éteint : le chef rentre à sa place.
+            if let Ok(mut cmd) = commands.get_entity(beam.beam) { cmd.despawn(); }
+            commands.entity(boss).remove::<(TractorBeam, TractorDive)>();
+            let home = formation.slot_position(slot);
+            *flight = Flight::Entering { start: b_pos, control: (b_pos + home) / 2.0 + Vec2::new(0.0, 80.0), progress: 0.0 };
+        }
+    }
+}
+
+pub fn capture_system( // Ton vaisseau monte en tournant vers le chef... et devient prisonnier.
+    mut commands: Commands,
+    time: Res<Time>,
+    tuning: Res<Tuning>,
+    mut lives: ResMut<Lives>,
+    boss_q: Query<&Transform, (With<Enemy>, Without<Player>)>,
+    mut player_q: Query<(Entity, &mut Transform, &BeingCaptured, &Sprite), With<Player>>,
+) {
+    let Ok((p_ent, mut p_trans, captured, sprite)) = player_q.single_mut() else { return };
+    let Ok(b_trans) = boss_q.get(captured.boss) else { // Le chef a disparu : on est relâché.
+        commands.entity(p_ent).remove::<BeingCaptured>();
+        p_trans.translation.y = -300.0;
+        p_trans.rotation = Quat::IDENTITY;
+        return;
+    };
+    let target = b_trans.translation.xy() - CAPTIVE_OFFSET;
+    let p_pos = p_trans.translation.xy();
+    let step = CAPTURE_SPEED * time.delta_secs();
+    p_trans.rotate_z(6.0 * time.delta_secs()); // Il tourne sur lui-même en montant.
+    if p_pos.distance(target) > step {
+        p_trans.translation += ((target - p_pos).normalize() * step).extend(0.0);
+        return;
+    }
+
+    commands.spawn(( // Le prisonnier : ton vaisseau, mais en rouge.
+        CapturedFighter { boss: captured.boss },
+        Collider::captive(&tuning), // On peut le toucher par erreur.
+        Sprite { image: sprite.image.clone(), custom_size: Some(tuning.player_size), color: Color::srgb(1.0, 0.3, 0.3), flip_y: true, ..default() },
+        Transform::from_translation(b_trans.translation + CAPTIVE_OFFSET.extend(0.0)),
+    ));
+    commands.entity(p_ent).despawn(); // Un vaisseau de perdu : le suivant arrivera quand la formation sera regroupée.
+    lives.lose_ship();
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn captive_system( // Le prisonnier suit son chef ; si le chef est abattu, il est libéré.
+    mut commands: Commands,
+    time: Res<Time>,
+    tuning: Res<Tuning>,
+    sprites: Res<Sprites>,
+    boss_q: Query<&Transform, (With<Enemy>, Without<CapturedFighter>, Without<RescuedFighter>)>,
+    mut captive_q: Query<(Entity, &CapturedFighter, &mut Transform, &mut Sprite), Without<RescuedFighter>>,
+    mut rescued_q: Query<(Entity, &mut Transform), (With<RescuedFighter>, Without<Player>, Without<Enemy>)>,
+    player_q: Query<(Entity, &Transform, Has<DualFighter>, Has<BeingCaptured>), (With<Player>, Without<Enemy>, Without<CapturedFighter>)>,
+) {
+    for (entity, captive, mut trans, mut sprite) in captive_q.iter_mut() {
+        match boss_q.get(captive.boss) {
+            Ok(b_trans) => trans.translation = b_trans.translation + CAPTIVE_OFFSET.extend(0.0),
+            Err(_) => { // Le chef est mort : le vaisseau est libre !
+                sprite.color = Color::WHITE;
+                sprite.flip_y = false;
+                commands.entity(entity).remove::<(CapturedFighter, Collider)>().insert(RescuedFighter);
+            }
+        }
+    }
+
+    for (entity, mut trans) in rescued_q.iter_mut() {
+        let Ok((p_ent, p_trans, dual, captured)) = player_q.single() else { commands.entity(entity).despawn(); continue };
+        if dual || captured { commands.entity(entity).despawn(); continue; } // Plus de place pour se coller : il repart.
+        let dock = p_trans.translation.xy() + Vec2::new(tuning.dual_offset(), 0.0);
+        let pos = trans.translation.xy();
+        let step = RESCUE_SPEED * time.delta_secs();
+        if pos.distance(dock) > step {
+            trans.translation += ((dock - pos).normalize() * step).extend(0.0);
+            continue;
+        }
+        commands.entity(entity).despawn(); // Il est arrivé : il devient l'ailier du joueur.
+        let wingman = commands.spawn((
+            Skin(SpriteId::Player),
+            Animation::play(animation::THRUST),
+            Sprite { custom_size: Some(tuning.player_size), ..sprites.sprite(SpriteId::Player) },
+            Transform::from_xyz(tuning.dual_offset(), 0.0, 0.0),
+        )).id();
+        commands.entity(p_ent).add_child(wingman).insert((DualFighter { wingman }, Collider::player(&tuning, true)));
+    }
+}
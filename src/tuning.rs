@@ -0,0 +1,285 @@
+// ═══════════════════════════════════════════════════════════════════════════
+// 🔧 LES RÉGLAGES : vitesses, tailles et chronos dans un fichier qu'on modifie en jouant
+// ═══════════════════════════════════════════════════════════════════════════
+//
+// Avant, la vitesse du vaisseau, des balles et des aliens, les tailles et les
+// chronos des vagues étaient des constantes de `main.rs` : pour essayer une
+// autre valeur, il fallait tout recompiler. Maintenant, ils sont dans
+// `assets/tuning.tuning.ron`. Le jeu surveille ce fichier et les fichiers de
+// niveaux : dès qu'on en enregistre un, le jeu en marche prend les nouvelles
+// valeurs et un petit message en bas de l'écran dit ce qui a changé.
+
+use std::time::Duration;
+
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoadFailedEvent, AssetLoader, AssetPath, LoadContext, UntypedAssetId};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::levels::{self, CampaignAsset, LevelAsset, LevelLoadError, Levels};
+use crate::lives::ExtraShips;
+use crate::tractor::DualFighter;
+use crate::paths::PathFollower;
+use crate::replay::Replay;
+use crate::{Bullet, Enemy, EnemyType, InGame, Movable, Player, WaveManager, WaveState};
+
+pub const TUNING_PATH: &str = "tuning.tuning.ron"; // Le fichier des réglages.
+const STATUS_DURATION: f32 = 4.0; // Le temps pendant lequel le message de rechargement reste affiché.
+const BOSS_SCALE: f32 = 2.5; // Un chef est 2,5 fois plus gros qu'un soldat.
+
+#[derive(Asset, TypePath, Resource, Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Tuning { // Les réglages du jeu (les valeurs par défaut sont celles d'avant le fichier).
+    pub player_speed: f32, // La vitesse de notre vaisseau.
+    pub bullet_speed: f32, // La vitesse des balles du joueur.
+    pub enemy_speed: f32, // La vitesse de base des aliens (leurs tirs et leurs plongeons en dépendent).
+    pub player_size: Vec2, // La taille du vaisseau du joueur.
+    pub enemy_size: Vec2, // La taille des petits aliens.
+    pub bullet_size: Vec2, // La taille des projectiles.
+    pub spawn_interval: f32, // Le temps entre deux aliens qui arrivent (si la vague ne dit rien).
+    pub wave_pause: f32, // Le repos entre deux vagues.
+    pub results_duration: f32, // Le temps pour lire le tableau du stage bonus.
+    pub extra_ships: ExtraShips, // Les scores qui donnent un vaisseau de plus (le "DIP switch" de la borne).
+    pub sprite_pack: String, // Le dossier du pack d'images, dans assets/sprites.
+}
+
+impl Default for Tuning {
+    fn default() -> Self {
+        Self {
+            player_speed: 500.0,
+            bullet_speed: 700.0,
+            enemy_speed: 120.0,
+            player_size: Vec2::new(30.0, 15.0),
+            enemy_size: Vec2::new(25.0, 25.0),
+            bullet_size: Vec2::new(5.0, 15.0),
+            spawn_interval: 0.6,
+            wave_pause: 2.0,
+            results_duration: 4.0,
+            extra_ships: ExtraShips::default(),
+            sprite_pack: "classic".into(),
+        }
+    }
+}
+
+impl Tuning {
+    pub fn enemy_size_of(&self, kind: EnemyType) -> Vec2 { // Les chefs sont plus gros que les soldats.
+        match kind { EnemyType::Soldier => self.enemy_size, EnemyType::Boss => self.enemy_size * BOSS_SCALE }
+    }
+
+    fn changes(&self, new: &Tuning) -> Vec<String> { // La liste lisible de ce qui a bougé entre deux versions.
+        let mut changes = Vec::new();
+        macro_rules! compare { ($($field:ident),*) => { $(
+            if self.$field != new.$field { changes.push(format!("{} {:?} -> {:?}", stringify!($field), self.$field, new.$field)); }
+        )* } }
+        compare!(player_speed, bullet_speed, enemy_speed, player_size, enemy_size, bullet_size, spawn_interval, wave_pause, results_duration, extra_ships, sprite_pack);
+        changes
+    }
+
+    fn validate(&self, text: &str, path: &str) -> Result<(), LevelLoadError> { // Des vitesses, tailles et chronos strictement positifs.
+        let invalid = |key: &str| LevelLoadError::Invalid {
+            path: path.to_string(),
+            line: text.find(&format!("{}:", key)).map(|i| levels::line_of(text, i)).unwrap_or(1),
+            message: format!("`{}` doit être > 0", key),
+        };
+        for (key, value) in [("player_speed", self.player_speed), ("bullet_speed", self.bullet_speed), ("enemy_speed", self.enemy_speed),
+                             ("spawn_interval", self.spawn_interval), ("wave_pause", self.wave_pause), ("results_duration", self.results_duration)] {
+            if value <= 0.0 { return Err(invalid(key)); }
+        }
+        for (key, size) in [("player_size", self.player_size), ("enemy_size", self.enemy_size), ("bullet_size", self.bullet_size)] {
+            if size.min_element() <= 0.0 { return Err(invalid(key)); }
+        }
+        if self.sprite_pack.trim().is_empty() { return Err(LevelLoadError::Invalid { path: path.to_string(), line: text.find("sprite_pack:").map(|i| levels::line_of(text, i)).unwrap_or(1), message: "`sprite_pack` ne peut pas être vide".into() }); }
+        Ok(())
+    }
+}
+
+#[derive(Default, TypePath)]
+pub struct TuningLoader; // Le lecteur du fichier `.tuning.ron`.
+
+impl AssetLoader for TuningLoader {
+    type Asset = Tuning;
+    type Settings = ();
+    type Error = LevelLoadError;
+
+    async fn load(&self, reader: &mut dyn Reader, _settings: &(), load_context: &mut LoadContext<'_>) -> Result<Tuning, LevelLoadError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let path = load_context.path().display().to_string();
+        let text = String::from_utf8_lossy(&bytes);
+        let tuning: Tuning = levels::parse(&text, &path)?;
+        tuning.validate(&text, &path)?;
+        Ok(tuning)
+    }
+
+    fn extensions(&self) -> &[&str] { &["tuning.ron"] }
+}
+
+#[derive(Resource)]
+pub struct TuningHandle(Handle<Tuning>); // Le fichier de réglages en cours de chargement (ou chargé).
+
+#[derive(Resource, Default)]
+pub struct StatusMessage { text: String, timer: Timer } // Le petit message en bas de l'écran ("réglages rechargés"...).
+
+#[derive(Component)] pub struct StatusText; // Étiquette pour le texte du message de rechargement.
+
+impl StatusMessage {
+    pub fn show(&mut self, text: String) { // On affiche un message pendant quelques secondes.
+        info!("{}", text);
+        self.text = text;
+        self.timer = Timer::from_seconds(STATUS_DURATION, TimerMode::Once);
+    }
+}
+
+pub fn load_tuning(mut commands: Commands, asset_server: Res<AssetServer>) { // On lance le chargement au démarrage.
+    commands.insert_resource(TuningHandle(asset_server.load(TUNING_PATH)));
+}
+
+pub fn tuning_ready(handle: Option<Res<TuningHandle>>, asset_server: Res<AssetServer>, assets: Res<Assets<Tuning>>, tuning: Res<Tuning>, replay: Res<Replay>) -> bool { // Les réglages du fichier sont-ils en place ?
+    if replay.recorded_tuning().is_some() { return true; } // Le replay a apporté les siens.
+    handle.is_some_and(|h| match assets.get(&h.0) {
+        Some(loaded) => loaded == &*tuning,
+        None => asset_server.load_state(&h.0).is_failed(), // Fichier absent ou illisible au démarrage : on joue avec les valeurs par défaut.
+    })
+}
+
+fn file_name(asset_server: &AssetServer, id: impl Into<UntypedAssetId>) -> String { // Le nom du fichier d'un asset, pour les messages.
+    asset_server.get_path(id).map(|p| p.path().display().to_string()).unwrap_or_else(|| "?".into())
+}
+
+fn failed_path(path: &AssetPath) -> String { path.path().display().to_string() }
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn apply_tuning_system( // Le fichier de réglages a été (re)chargé : on prend les nouvelles valeurs tout de suite.
+    mut events: MessageReader<AssetEvent<Tuning>>,
+    handle: Option<Res<TuningHandle>>,
+    assets: Res<Assets<Tuning>>,
+    mut tuning: ResMut<Tuning>,
+    mut wave_mgr: ResMut<WaveManager>,
+    mut status: ResMut<StatusMessage>,
+    replay: Res<Replay>,
+    in_game: Option<Res<State<InGame>>>,
+    dual_q: Query<&DualFighter>,
+    mut bullets: Query<(&Bullet, &mut Movable)>,
+    mut followers: Query<&mut PathFollower>,
+    mut sprites: ParamSet<(
+        Query<&mut Sprite, With<Player>>,
+        Query<(&Enemy, &mut Sprite)>,
+        Query<&mut Sprite, With<Bullet>>,
+        Query<(&mut Sprite, &mut Transform)>,
+    )>,
+) {
+    let Some(handle) = handle else { return };
+    let reloaded = events.read().any(|event| match event {
+        AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } => *id == handle.0.id(),
+        _ => false,
+    });
+    if !reloaded { return; }
+    if replay.recorded_tuning().is_some() { return; } // On rejoue avec les réglages du replay, pas ceux du fichier.
+    let Some(new) = assets.get(&handle.0) else { return };
+    let changes = tuning.changes(new);
+    if changes.is_empty() { return; }
+    if replay.is_recording() && in_game.is_some() { // Le replay ne garde que les réglages du début de la partie (entre deux parties, on peut).
+        status.show("Réglages pas rechargés : un replay est en cours d'enregistrement".into());
+        return;
+    }
+
+    wave_mgr.wave_timer.set_duration(Duration::from_secs_f32(new.wave_pause)); // Les chronos en cours gardent leur avance.
+    wave_mgr.results_timer.set_duration(Duration::from_secs_f32(new.results_duration));
+    let (bullet_factor, enemy_factor) = (new.bullet_speed / tuning.bullet_speed, new.enemy_speed / tuning.enemy_speed);
+    for (bullet, mut movable) in bullets.iter_mut() { // Les tirs déjà en route changent de vitesse, sans changer de direction.
+        if bullet.from_player { movable.velocity.y *= bullet_factor; } else { movable.velocity *= enemy_factor; }
+    }
+    for mut follower in followers.iter_mut() { follower.scale_speed(enemy_factor); } // Les plongeons et les acrobates aussi.
+    for mut sprite in sprites.p0().iter_mut() { sprite.custom_size = Some(new.player_size); } // On redimensionne ce qui est déjà à l'écran.
+    for (enemy, mut sprite) in sprites.p1().iter_mut() { sprite.custom_size = Some(new.enemy_size_of(enemy.kind)); }
+    for mut sprite in sprites.p2().iter_mut() { sprite.custom_size = Some(new.bullet_size); }
+    for dual in dual_q.iter() {
+        if let Ok((mut sprite, mut trans)) = sprites.p3().get_mut(dual.wingman) {
+            sprite.custom_size = Some(new.player_size);
+            trans.translation.x = new.dual_offset();
+        }
+    }
+    status.show(format!("Réglages rechargés : {}", changes.join(", ")));
+    *tuning = new.clone();
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn level_reload_status_system( // On dit quand un fichier de niveau a été relu, ou pourquoi il a été refusé.
+    mut level_events: MessageReader<AssetEvent<LevelAsset>>,
+    mut campaign_events: MessageReader<AssetEvent<CampaignAsset>>,
+    mut level_failures: MessageReader<AssetLoadFailedEvent<LevelAsset>>,
+    mut campaign_failures: MessageReader<AssetLoadFailedEvent<CampaignAsset>>,
+    mut tuning_failures: MessageReader<AssetLoadFailedEvent<Tuning>>,
+    asset_server: Res<AssetServer>,
+    levels: Res<Assets<LevelAsset>>,
+    tuning_handle: Option<Res<TuningHandle>>,
+    tunings: Res<Assets<Tuning>>,
+    mut status: ResMut<StatusMessage>,
+) {
+    for event in level_events.read() {
+        if let AssetEvent::Modified { id } = event {
+            let name = levels.get(*id).map(|l| l.name.as_str()).unwrap_or("?");
+            status.show(format!("Niveau rechargé : {} ({})", name, file_name(&asset_server, *id)));
+        }
+    }
+    for event in campaign_events.read() {
+        if let AssetEvent::Modified { id } = event { status.show(format!("Campagne rechargée ({})", file_name(&asset_server, *id))); }
+    }
+    // En cas d'erreur, le jeu garde l'ancienne version du fichier.
+    for failure in level_failures.read() { status.show(format!("Niveau refusé ({}) : {}", failed_path(&failure.path), failure.error)); }
+    for failure in campaign_failures.read() { status.show(format!("Campagne refusée ({}) : {}", failed_path(&failure.path), failure.error)); }
+    for failure in tuning_failures.read() {
+        let fallback = if tuning_handle.as_ref().is_some_and(|h| !tunings.contains(&h.0)) { " ; valeurs par défaut" } else { "" }; // Jamais chargé : rien d'ancien à garder.
+        status.show(format!("Réglages refusés ({}) : {}{}", failed_path(&failure.path), failure.error, fallback));
+    }
+}
+
+pub fn level_progress_system(levels: Levels, mut wave_mgr: ResMut<WaveManager>, mut status: ResMut<StatusMessage>) { // Un fichier raccourci ne doit pas bloquer la partie.
+    if !levels.all_loaded() { return; }
+    let Some((level, wave)) = levels.clamp_progress(wave_mgr.current_level, wave_mgr.current_wave) else { return };
+    wave_mgr.current_level = level;
+    wave_mgr.current_wave = wave;
+    if wave_mgr.state == WaveState::Spawning { wave_mgr.state = WaveState::Fighting; } // Les aliens déjà là finissent la vague, puis on continue.
+    status.show(format!("Niveau raccourci : on reprend au niveau {}, vague {}", level, wave));
+}
+
+pub fn status_text_system(time: Res<Time>, mut status: ResMut<StatusMessage>, mut text_q: Query<&mut Text, With<StatusText>>) { // Le message s'efface tout seul.
+    status.timer.tick(time.delta());
+    let Ok(mut text) = text_q.single_mut() else { return };
+    let shown = if status.timer.is_finished() { "" } else { status.text.as_str() };
+    if text.0 != shown { text.0 = shown.to_string(); }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT: &str = "(\n    player_speed: 500.0,\n    enemy_size: (25.0, 25.0),\n    sprite_pack: \"classic\",\n)";
+
+    fn refused(tuning: Tuning) -> Option<(usize, String)> { // La ligne et le message de l'erreur, si le fichier est refusé.
+        match tuning.validate(TEXT, "tuning.tuning.ron") {
+            Err(LevelLoadError::Invalid { line, message, .. }) => Some((line, message)),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn the_defaults_are_valid() {
+        assert_eq!(refused(Tuning::default()), None);
+    }
+
+    #[test]
+    fn zero_or_negative_values_point_at_their_line() {
+        assert_eq!(refused(Tuning { player_speed: 0.0, ..default() }), Some((2, "`player_speed` doit être > 0".into())));
+        assert_eq!(refused(Tuning { enemy_size: Vec2::new(25.0, -1.0), ..default() }), Some((3, "`enemy_size` doit être > 0".into())));
+        assert_eq!(refused(Tuning { wave_pause: -2.0, ..default() }), Some((1, "`wave_pause` doit être > 0".into())), "absent du fichier : la première ligne");
+        assert_eq!(refused(Tuning { sprite_pack: " ".into(), ..default() }), Some((4, "`sprite_pack` ne peut pas être vide".into())));
+    }
+
+    #[test]
+    fn changes_name_each_field_that_moved() {
+        let old = Tuning::default();
+        assert!(old.changes(&old.clone()).is_empty());
+        let new = Tuning { bullet_speed: 900.0, sprite_pack: "neon".into(), ..default() };
+        assert_eq!(old.changes(&new), ["bullet_speed 700.0 -> 900.0", "sprite_pack \"classic\" -> \"neon\""]);
+    }
+}